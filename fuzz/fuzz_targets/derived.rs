@@ -0,0 +1,103 @@
+#![no_main]
+
+use std::collections::HashMap;
+
+use libfuzzer_sys::fuzz_target;
+use memorypack::{
+    MemoryPackDeserialize, MemoryPackDeserializeZeroCopy, MemoryPackReader,
+    MemoryPackReaderLimits, MemoryPackable,
+};
+
+#[derive(MemoryPackable, Default)]
+struct Plain {
+    id: i32,
+    name: String,
+    tags: Vec<String>,
+    scores: HashMap<String, f64>,
+    parent: Option<Box<Plain>>,
+}
+
+#[derive(MemoryPackable, Default)]
+#[memorypack(version_tolerant)]
+struct Tolerant {
+    #[memorypack(order = 0)]
+    id: i64,
+    #[memorypack(order = 2)]
+    name: String,
+    #[memorypack(order = 5)]
+    children: Vec<Tolerant>,
+}
+
+#[derive(MemoryPackable, Default, Clone)]
+#[memorypack(circular)]
+struct Circular {
+    #[memorypack(order = 0)]
+    value: i32,
+    #[memorypack(order = 1)]
+    next: Option<Box<Circular>>,
+}
+
+#[derive(MemoryPackable, Clone, Copy)]
+#[repr(i32)]
+enum Color {
+    Red,
+    Green,
+    Blue,
+}
+
+#[derive(MemoryPackable, Clone, Copy)]
+enum Status {
+    Active = 1,
+    Disabled = 7,
+}
+
+#[derive(MemoryPackable)]
+enum Message {
+    #[memorypack(tag = 0)]
+    Ping,
+    #[memorypack(tag = 1)]
+    Text { body: String, color: Color },
+    #[memorypack(tag = 2)]
+    Nested(Plain),
+    #[memorypack(tag = 300)]
+    Batch(Vec<Message>, Status),
+}
+
+#[derive(MemoryPackable)]
+#[memorypack(zero_copy)]
+struct Borrowed<'a> {
+    name: &'a str,
+    bytes: &'a [u8],
+    items: Vec<&'a str>,
+}
+
+fn limits() -> MemoryPackReaderLimits {
+    MemoryPackReaderLimits::default().with_max_allocated_bytes(1 << 20)
+}
+
+/// Decode `T` from the payload; only a returned error is acceptable for malformed input
+fn decode<T: MemoryPackDeserialize>(data: &[u8]) {
+    let mut reader = MemoryPackReader::new(data).with_limits(limits());
+    let _ = T::deserialize(&mut reader);
+}
+
+fn decode_borrowed<'a, T: MemoryPackDeserializeZeroCopy<'a>>(data: &'a [u8]) {
+    let mut reader = MemoryPackReader::new(data).with_limits(limits());
+    let _ = T::deserialize(&mut reader);
+}
+
+fuzz_target!(|data: &[u8]| {
+    let Some((&selector, payload)) = data.split_first() else {
+        return;
+    };
+
+    match selector % 7 {
+        0 => decode::<Plain>(payload),
+        1 => decode::<Tolerant>(payload),
+        2 => decode::<Circular>(payload),
+        3 => decode::<(Color, Status)>(payload),
+        4 => decode::<Message>(payload),
+        5 => decode::<Option<Message>>(payload),
+        _ => decode_borrowed::<Borrowed>(payload),
+    }
+});
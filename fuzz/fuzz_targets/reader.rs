@@ -0,0 +1,42 @@
+#![no_main]
+
+use std::collections::{BTreeMap, HashMap, HashSet, LinkedList, VecDeque};
+
+use libfuzzer_sys::fuzz_target;
+use memorypack::{MemoryPackDeserialize, MemoryPackReader, MemoryPackReaderLimits, MultiDimArray};
+
+/// Decode `T` from the payload; only a returned error is acceptable for malformed input
+fn decode<T: MemoryPackDeserialize>(data: &[u8]) {
+    let limits = MemoryPackReaderLimits::default().with_max_allocated_bytes(1 << 20);
+    let mut reader = MemoryPackReader::new(data).with_limits(limits);
+    let _ = T::deserialize(&mut reader);
+}
+
+fuzz_target!(|data: &[u8]| {
+    let Some((&selector, payload)) = data.split_first() else {
+        return;
+    };
+
+    match selector % 20 {
+        0 => decode::<(bool, u8, i16, u32, i64, f32, f64)>(payload),
+        1 => decode::<char>(payload),
+        2 => decode::<String>(payload),
+        3 => decode::<Option<String>>(payload),
+        4 => decode::<Vec<u8>>(payload),
+        5 => decode::<Vec<String>>(payload),
+        6 => decode::<Vec<Vec<i32>>>(payload),
+        7 => decode::<HashMap<String, i32>>(payload),
+        8 => decode::<BTreeMap<i32, Vec<u8>>>(payload),
+        9 => decode::<HashSet<i64>>(payload),
+        10 => decode::<[u16; 4]>(payload),
+        11 => decode::<(VecDeque<String>, LinkedList<u64>)>(payload),
+        12 => decode::<MultiDimArray<i32>>(payload),
+        13 => decode::<uuid::Uuid>(payload),
+        14 => decode::<rust_decimal::Decimal>(payload),
+        15 => decode::<num_bigint::BigInt>(payload),
+        16 => decode::<(chrono::TimeDelta, chrono::NaiveTime)>(payload),
+        17 => decode::<chrono::DateTime<chrono::FixedOffset>>(payload),
+        18 => decode::<chrono::DateTime<chrono::Utc>>(payload),
+        _ => decode::<url::Url>(payload),
+    }
+});
@@ -0,0 +1,114 @@
+#![no_main]
+
+use std::collections::HashMap;
+use std::io::Read;
+
+use libfuzzer_sys::fuzz_target;
+use memorypack::serde::{Deserializer, from_slice};
+use memorypack::{MemoryPackDeserialize, MemoryPackReader, MemoryPackReaderLimits, MemoryPackable};
+
+#[derive(MemoryPackable, Default)]
+struct Plain {
+    id: i32,
+    name: String,
+    tags: Vec<String>,
+    scores: HashMap<String, f64>,
+    parent: Option<Box<Plain>>,
+}
+
+#[derive(MemoryPackable, Default)]
+#[memorypack(version_tolerant)]
+struct Tolerant {
+    #[memorypack(order = 0)]
+    id: i64,
+    #[memorypack(order = 2)]
+    name: String,
+    #[memorypack(order = 5)]
+    children: Vec<Tolerant>,
+}
+
+#[derive(MemoryPackable)]
+enum Message {
+    #[memorypack(tag = 0)]
+    Ping,
+    #[memorypack(tag = 1)]
+    Text { body: String },
+    #[memorypack(tag = 300)]
+    Batch(Vec<Message>),
+}
+
+#[derive(serde::Deserialize)]
+#[allow(dead_code)]
+struct Record<'a> {
+    id: u32,
+    name: String,
+    label: &'a str,
+    #[serde(with = "serde_bytes")]
+    bytes: &'a [u8],
+    values: Vec<(i16, f64)>,
+    lookup: HashMap<String, Vec<u8>>,
+    kind: Kind,
+}
+
+#[derive(serde::Deserialize)]
+#[allow(dead_code)]
+enum Kind {
+    Empty,
+    Number(i64),
+    Pair(u8, char),
+    Named { children: Vec<Kind> },
+}
+
+/// `Read` handing out at most `chunk` bytes per call, so the stream reader refills mid-value
+struct Chunked<'a> {
+    data: &'a [u8],
+    chunk: usize,
+}
+
+impl Read for Chunked<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let len = buf.len().min(self.chunk).min(self.data.len());
+        buf[..len].copy_from_slice(&self.data[..len]);
+        self.data = &self.data[len..];
+        Ok(len)
+    }
+}
+
+fn limits() -> MemoryPackReaderLimits {
+    MemoryPackReaderLimits::default().with_max_allocated_bytes(1 << 20)
+}
+
+/// Decode `T` from a stream over the payload; only a returned error is acceptable
+fn decode_stream<T: MemoryPackDeserialize>(data: &[u8], chunk: usize) {
+    let mut source = Chunked { data, chunk };
+    let mut reader = MemoryPackReader::from_stream(&mut source).with_limits(limits());
+    let _ = T::deserialize(&mut reader);
+}
+
+/// Decode a serde type from both the slice and the stream reader
+fn decode_serde(data: &[u8], chunk: usize) {
+    let _ = from_slice::<Record>(data);
+
+    let mut source = Chunked { data, chunk };
+    let mut reader = MemoryPackReader::from_stream(&mut source).with_limits(limits());
+    let mut deserializer = Deserializer::new(&mut reader);
+    let _ = <(String, Vec<Kind>, HashMap<i32, String>) as serde::Deserialize>::deserialize(&mut deserializer);
+}
+
+fuzz_target!(|data: &[u8]| {
+    let Some((&selector, payload)) = data.split_first() else {
+        return;
+    };
+
+    // The low bits pick the type, the high bits how many bytes each read returns
+    let chunk = 1 + (selector >> 3) as usize;
+    match selector & 7 {
+        0 => decode_stream::<Plain>(payload, chunk),
+        1 => decode_stream::<Tolerant>(payload, chunk),
+        2 => decode_stream::<Message>(payload, chunk),
+        3 => decode_stream::<Option<Message>>(payload, chunk),
+        4 => decode_stream::<(String, Vec<u8>, Option<String>)>(payload, chunk),
+        5 => decode_stream::<HashMap<String, Vec<i32>>>(payload, chunk),
+        _ => decode_serde(payload, chunk),
+    }
+});
@@ -76,7 +76,9 @@ struct BarClass {
 #[derive(MemoryPackable, Clone)]
 #[memorypack(union)]
 enum UnionSample {
+    #[memorypack(tag = 0)]
     Foo(FooClass),
+    #[memorypack(tag = 1)]
     Bar(BarClass),
 }
 
@@ -5,7 +5,9 @@ pub struct AttributeFlags {
     pub is_version_tolerant: bool,
     pub is_circular: bool,
     pub is_zero_copy: bool,
+    pub is_unmanaged: bool,
     pub has_repr_i32: bool,
+    pub has_repr_c: bool,
 }
 
 impl AttributeFlags {
@@ -17,7 +19,9 @@ impl AttributeFlags {
             is_version_tolerant: false,
             is_circular: false,
             is_zero_copy: false,
+            is_unmanaged: false,
             has_repr_i32: false,
+            has_repr_c: false,
         };
 
         for attr in attrs {
@@ -27,6 +31,7 @@ impl AttributeFlags {
                         let tokens = list.tokens.to_string();
                         result.is_transparent = tokens.contains("transparent");
                         result.has_repr_i32 = tokens.contains("i32");
+                        result.has_repr_c = tokens.split(',').any(|repr| repr.trim() == "C");
                     }
                 }
                 path if path.is_ident("memorypack") => {
@@ -37,6 +42,7 @@ impl AttributeFlags {
                         result.is_version_tolerant = tokens.contains("version_tolerant");
                         result.is_circular = tokens.contains("circular");
                         result.is_zero_copy = tokens.contains("zero_copy");
+                        result.is_unmanaged = tokens.contains("unmanaged");
                     }
                 }
                 _ => {}
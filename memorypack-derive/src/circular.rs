@@ -1,4 +1,7 @@
-use crate::helpers::{is_option_box, prepare_ordered_fields, should_skip_field};
+use crate::helpers::{
+    field_default, field_deserialize_call, field_serialize_call, is_option_box, member_context,
+    member_name, prepare_ordered_fields, should_skip_field,
+};
 
 use quote::quote;
 use syn::{Data, Fields};
@@ -21,165 +24,105 @@ pub fn generate_circular_serialize(data: &Data, needs_state: bool) -> proc_macro
             let max_order = ordered.last().map(|f| f.order).unwrap_or(0);
             let member_count = max_order + 1;
 
-            let field_serialization: Vec<_> = ordered.iter().map(|of| {
+            let field_serialization: Vec<_> = (0..member_count).map(|order| {
+                let Some(of) = ordered.iter().find(|f| f.order == order) else {
+                    return quote! { field_lengths[#order] = 0; };
+                };
                 let name = of.ident;
-                let field = of.field;
-
-                if is_option_box(&field.ty) {
-                    quote! {
-                        let mut temp_writer = memorypack::MemoryPackWriter::new();
-                        match &self.#name {
-                            None => {
-                                temp_writer.write_u8(255)?;
-                            }
-                            Some(boxed_value) => {
-                                memorypack::MemoryPackSerialize::serialize(&**boxed_value, &mut temp_writer)?;
-                            }
-                        }
-                        field_buffers.push(temp_writer.buffer);
-                    }
-                } else {
-                    quote! {
-                        let mut temp_writer = memorypack::MemoryPackWriter::new();
-                        memorypack::MemoryPackSerialize::serialize(&self.#name, &mut temp_writer)?;
-                        field_buffers.push(temp_writer.buffer);
-                    }
-                }
-            }).collect();
+                let serialize_field = generate_circular_field_serialize(of.field, quote! { self.#name });
 
-            if needs_state {
                 quote! {
-                    if writer.optional_state.is_none() {
-                        writer.optional_state = Some(memorypack::MemoryPackWriterOptionalState::new());
-                    }
-
-                    let (is_existing, ref_id) = writer.optional_state.as_mut().unwrap().get_or_add_reference(self);
-
-                    if is_existing {
-                        writer.write_u8(250)?;
-                        memorypack::varint::write_varint(writer, ref_id as i64)?;
-                        return Ok(());
-                    }
-
-                    writer.write_u8(#member_count as u8)?;
-                    let mut field_buffers: Vec<Vec<u8>> = Vec::new();
-                    #(#field_serialization)*
-
-                    for i in 0..#member_count {
-                        if i < field_buffers.len() {
-                            memorypack::varint::write_varint(writer, field_buffers[i].len() as i64)?;
-                        } else {
-                            memorypack::varint::write_varint(writer, 0)?;
-                        }
-                    }
-
-                    memorypack::varint::write_varint(writer, ref_id as i64)?;
-
-                    for buf in field_buffers {
-                        writer.buffer.extend_from_slice(&buf);
-                    }
+                    let start = writer.position();
+                    #serialize_field
+                    field_lengths[#order] = writer.position() - start;
                 }
-            } else {
-                quote! {
-                    writer.write_u8(#member_count as u8)?;
-                    let mut field_buffers: Vec<Vec<u8>> = Vec::new();
-                    #(#field_serialization)*
-
-                    for i in 0..#member_count {
-                        if i < field_buffers.len() {
-                            memorypack::varint::write_varint(writer, field_buffers[i].len() as i64)?;
-                        } else {
-                            memorypack::varint::write_varint(writer, 0)?;
-                        }
-                    }
-
-                    memorypack::varint::write_varint(writer, 0)?;
+            }).collect();
 
-                    for buf in field_buffers {
-                        writer.buffer.extend_from_slice(&buf);
-                    }
-                }
-            }
+            generate_circular_object_serialize(member_count, &field_serialization, needs_state)
         }
         Fields::Unnamed(fields) => {
             let field_count = fields.unnamed.len();
-            let field_indices: Vec<_> = (0..field_count).collect();
 
-            let field_serialization: Vec<_> = field_indices.iter().map(|i| {
-                let idx = syn::Index::from(*i);
-                let field = &fields.unnamed[*i];
+            let field_serialization: Vec<_> = fields.unnamed.iter().enumerate().map(|(i, field)| {
+                let idx = syn::Index::from(i);
+                let serialize_field = generate_circular_field_serialize(field, quote! { self.#idx });
 
-                if is_option_box(&field.ty) {
-                    quote! {
-                        let mut temp_writer = memorypack::MemoryPackWriter::new();
-                        match &self.#idx {
-                            None => {
-                                temp_writer.write_u8(255)?;
-                            }
-                            Some(boxed_value) => {
-                                memorypack::MemoryPackSerialize::serialize(&**boxed_value, &mut temp_writer)?;
-                            }
-                        }
-                        field_buffers.push(temp_writer.buffer);
-                    }
-                } else {
-                    quote! {
-                        let mut temp_writer = memorypack::MemoryPackWriter::new();
-                        memorypack::MemoryPackSerialize::serialize(&self.#idx, &mut temp_writer)?;
-                        field_buffers.push(temp_writer.buffer);
-                    }
+                quote! {
+                    let start = writer.position();
+                    #serialize_field
+                    field_lengths[#i] = writer.position() - start;
                 }
             }).collect();
 
-            if needs_state {
-                quote! {
-                    if writer.optional_state.is_none() {
-                        writer.optional_state = Some(memorypack::MemoryPackWriterOptionalState::new());
-                    }
-
-                    let (is_existing, ref_id) = writer.optional_state.as_mut().unwrap().get_or_add_reference(self);
+            generate_circular_object_serialize(field_count, &field_serialization, needs_state)
+        }
+        Fields::Unit => quote! {
+            writer.write_u8(0)?;
+        },
+    }
+}
 
-                    if is_existing {
-                        writer.write_u8(250)?;
-                        memorypack::varint::write_varint(writer, ref_id as i64)?;
-                        return Ok(());
-                    }
+fn generate_circular_field_serialize(
+    field: &syn::Field,
+    access: proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    if is_option_box(&field.ty) {
+        quote! {
+            match &#access {
+                None => {
+                    writer.write_u8(255)?;
+                }
+                Some(boxed_value) => {
+                    memorypack::MemoryPackSerialize::serialize(&**boxed_value, writer)?;
+                }
+            }
+        }
+    } else {
+        field_serialize_call(field, quote! { &#access })
+    }
+}
 
-                    writer.write_u8(#field_count as u8)?;
-                    let mut field_buffers: Vec<Vec<u8>> = Vec::new();
-                    #(#field_serialization)*
+fn generate_circular_object_serialize(
+    member_count: usize,
+    field_serialization: &[proc_macro2::TokenStream],
+    needs_state: bool,
+) -> proc_macro2::TokenStream {
+    let header_len = member_count + 1;
+
+    let reference = if needs_state {
+        quote! {
+            if writer.optional_state.is_none() {
+                writer.optional_state = Some(memorypack::MemoryPackWriterOptionalState::new());
+            }
 
-                    for buf in &field_buffers {
-                        memorypack::varint::write_varint(writer, buf.len() as i64)?;
-                    }
+            let (is_existing, ref_id) = writer.optional_state.as_mut().unwrap().get_or_add_reference(self);
 
-                    memorypack::varint::write_varint(writer, ref_id as i64)?;
+            if is_existing {
+                writer.write_u8(250)?;
+                memorypack::varint::write_varint(writer, ref_id as i64)?;
+                return Ok(());
+            }
+        }
+    } else {
+        quote! { let ref_id = 0u32; }
+    };
 
-                    for buf in field_buffers {
-                        writer.buffer.extend_from_slice(&buf);
-                    }
-                }
-            } else {
-                quote! {
-                    writer.write_u8(#field_count as u8)?;
-                    let mut field_buffers: Vec<Vec<u8>> = Vec::new();
-                    #(#field_serialization)*
+    quote! {
+        #reference
 
-                    for buf in &field_buffers {
-                        memorypack::varint::write_varint(writer, buf.len() as i64)?;
-                    }
+        writer.write_u8(#member_count as u8)?;
+        let header_reservation = writer.reserve(0)?;
 
-                    memorypack::varint::write_varint(writer, 0)?;
+        let mut field_lengths = [0usize; #member_count];
+        #(#field_serialization)*
 
-                    for buf in field_buffers {
-                        writer.buffer.extend_from_slice(&buf);
-                    }
-                }
-            }
+        let mut header = [0u8; #header_len * memorypack::varint::MAX_VARINT_LENGTH];
+        let mut header_written = 0;
+        for &len in &field_lengths {
+            header_written += memorypack::varint::encode_varint(len as i64, &mut header[header_written..]);
         }
-        Fields::Unit => quote! {
-            writer.write_u8(0)?;
-        },
+        header_written += memorypack::varint::encode_varint(ref_id as i64, &mut header[header_written..]);
+        writer.commit(header_reservation, &header[..header_written])?;
     }
 }
 
@@ -199,26 +142,30 @@ pub fn generate_circular_deserialize(data: &Data, needs_state: bool) -> proc_mac
                 .filter(|f| !should_skip_field(f))
                 .collect();
 
+            let all_field_names: Vec<_> = fields.named.iter().map(|f| &f.ident).collect();
+            let all_field_defaults: Vec<_> = fields.named.iter().map(field_default).collect();
+
             if non_skip.is_empty() {
                 return quote! {
-                    if reader.optional_state.is_none() {
-                        reader.optional_state = Some(memorypack::MemoryPackReaderOptionalState::new());
-                    }
-
                     let member_count_or_ref = reader.read_u8()?;
                     if member_count_or_ref == 250 {
                         let ref_id = memorypack::varint::read_varint(reader)? as u32;
-                        return reader.optional_state.as_ref().unwrap().get_object_reference::<Self>(ref_id);
+                        return reader.optional_state_mut().get_object_reference::<Self>(ref_id);
                     }
-                    let result = Self {};
+                    let result = Self { #(#all_field_names: #all_field_defaults),* };
                     let ref_id = memorypack::varint::read_varint(reader)? as u32;
-                    reader.optional_state.as_mut().unwrap().add_object_reference(ref_id, result.clone())?;
+                    reader.optional_state_mut().add_object_reference(ref_id, result.clone())?;
                     Ok(result)
                 };
             }
 
             let ordered = prepare_ordered_fields(&non_skip);
-            let all_field_names: Vec<_> = fields.named.iter().map(|f| &f.ident).collect();
+
+            let skipped_fields = fields.named.iter().filter(|f| should_skip_field(f)).map(|f| {
+                let name = &f.ident;
+                let default = field_default(f);
+                quote! { let #name = #default; }
+            });
 
             let deserialize_logic: Vec<_> = ordered.iter().map(|of| {
                 let name = of.ident;
@@ -226,14 +173,17 @@ pub fn generate_circular_deserialize(data: &Data, needs_state: bool) -> proc_mac
                 let field = of.field;
 
                 if is_option_box(&field.ty) {
+                    let deserialize = member_context(
+                        quote! { memorypack::MemoryPackDeserialize::deserialize(reader) },
+                        &member_name(field, 0),
+                    );
                     quote! {
                         let #name = if #order < member_count && lengths[#order] > 0 {
-                            let first_byte = reader.read_u8()?;
-                            if first_byte == 255 {
+                            if reader.peek_u8()? == 255 {
+                                reader.read_u8()?;
                                 None
                             } else {
-                                reader.rewind(1)?;
-                                Some(Box::new(memorypack::MemoryPackDeserialize::deserialize(reader)?))
+                                Some(Box::new(#deserialize))
                             }
                         } else {
                             if #order < member_count {
@@ -243,14 +193,16 @@ pub fn generate_circular_deserialize(data: &Data, needs_state: bool) -> proc_mac
                         };
                     }
                 } else {
+                    let deserialize = field_deserialize_call(field, &member_name(field, 0), None);
+                    let default = field_default(field);
                     quote! {
                         let #name = if #order < member_count && lengths[#order] > 0 {
-                            memorypack::MemoryPackDeserialize::deserialize(reader)?
+                            #deserialize
                         } else {
                             if #order < member_count {
                                 reader.skip(lengths[#order])?;
                             }
-                            Default::default()
+                            #default
                         };
                     }
                 }
@@ -268,32 +220,30 @@ pub fn generate_circular_deserialize(data: &Data, needs_state: bool) -> proc_mac
             };
 
             quote! {
-                if reader.optional_state.is_none() {
-                    reader.optional_state = Some(memorypack::MemoryPackReaderOptionalState::new());
-                }
 
                 let member_count_or_ref = reader.read_u8()?;
 
                 if member_count_or_ref == 250 {
                     let ref_id = memorypack::varint::read_varint(reader)? as u32;
-                    return reader.optional_state.as_ref().unwrap().get_object_reference::<Self>(ref_id);
+                    return reader.optional_state_mut().get_object_reference::<Self>(ref_id);
                 }
 
                 let member_count = member_count_or_ref as usize;
                 let mut lengths = Vec::with_capacity(member_count);
                 for _ in 0..member_count {
-                    lengths.push(memorypack::varint::read_varint(reader)? as usize);
+                    lengths.push(memorypack::varint::read_varint_length(reader)?);
                 }
                 let ref_id = memorypack::varint::read_varint(reader)? as u32;
 
-                let placeholder = Self { #(#all_field_names: Default::default()),* };
-                reader.optional_state.as_mut().unwrap().add_object_reference(ref_id, placeholder)?;
+                let placeholder = Self { #(#all_field_names: #all_field_defaults),* };
+                reader.optional_state_mut().add_object_reference(ref_id, placeholder)?;
 
                 #(#deserialize_logic)*
                 #skip_extra_fields
+                #(#skipped_fields)*
 
                 let result = Self { #(#all_field_names),* };
-                reader.optional_state.as_mut().unwrap().update_object_reference(ref_id, result.clone())?;
+                reader.optional_state_mut().update_object_reference(ref_id, result.clone())?;
 
                 Ok(result)
             }
@@ -304,32 +254,31 @@ pub fn generate_circular_deserialize(data: &Data, needs_state: bool) -> proc_mac
                 .map(|i| syn::Ident::new(&format!("field_{}", i), proc_macro2::Span::call_site()))
                 .collect();
 
-            let deserialize_fields = field_vars.iter().enumerate().map(|(i, var)| {
+            let deserialize_fields = fields.unnamed.iter().zip(&field_vars).enumerate().map(|(i, (field, var))| {
+                let deserialize = field_deserialize_call(field, &member_name(field, i), None);
+                let default = field_default(field);
                 quote! {
                     let #var = if #i < member_count {
-                        memorypack::MemoryPackDeserialize::deserialize(reader)?
+                        #deserialize
                     } else {
-                        Default::default()
+                        #default
                     };
                 }
             });
 
             quote! {
-                if reader.optional_state.is_none() {
-                    reader.optional_state = Some(memorypack::MemoryPackReaderOptionalState::new());
-                }
 
                 let member_count_or_ref = reader.read_u8()?;
 
                 if member_count_or_ref == 250 {
                     let ref_id = memorypack::varint::read_varint(reader)? as u32;
-                    return reader.optional_state.as_ref().unwrap().get_object_reference::<Self>(ref_id);
+                    return reader.optional_state_mut().get_object_reference::<Self>(ref_id);
                 }
 
                 let member_count = member_count_or_ref as usize;
                 let mut lengths = Vec::with_capacity(member_count);
                 for _ in 0..member_count {
-                    lengths.push(memorypack::varint::read_varint(reader)? as usize);
+                    lengths.push(memorypack::varint::read_varint_length(reader)?);
                 }
                 let ref_id = memorypack::varint::read_varint(reader)? as u32;
 
@@ -339,24 +288,21 @@ pub fn generate_circular_deserialize(data: &Data, needs_state: bool) -> proc_mac
                 }
 
                 let result = Self(#(#field_vars),*);
-                reader.optional_state.as_mut().unwrap().add_object_reference(ref_id, result.clone())?;
+                reader.optional_state_mut().add_object_reference(ref_id, result.clone())?;
 
                 Ok(result)
             }
         }
         Fields::Unit => quote! {
-            if reader.optional_state.is_none() {
-                reader.optional_state = Some(memorypack::MemoryPackReaderOptionalState::new());
-            }
 
             let member_count_or_ref = reader.read_u8()?;
             if member_count_or_ref == 250 {
                 let ref_id = memorypack::varint::read_varint(reader)? as u32;
-                return reader.optional_state.as_ref().unwrap().get_object_reference::<Self>(ref_id);
+                return reader.optional_state_mut().get_object_reference::<Self>(ref_id);
             }
             let result = Self;
             let ref_id = memorypack::varint::read_varint(reader)? as u32;
-            reader.optional_state.as_mut().unwrap().add_object_reference(ref_id, result.clone())?;
+            reader.optional_state_mut().add_object_reference(ref_id, result.clone())?;
             Ok(result)
         },
     }
@@ -7,20 +7,14 @@ pub fn generate_enum_serialize() -> proc_macro2::TokenStream {
     }
 }
 
-pub fn generate_enum_deserialize_unsafe() -> proc_macro2::TokenStream {
-    quote! {
-        let value = reader.read_i32()?;
-        Ok(unsafe { std::mem::transmute(value) })
-    }
-}
-
-pub fn generate_enum_deserialize_safe(data_enum: &syn::DataEnum) -> proc_macro2::TokenStream {
+/// Match the wire value against each variant's discriminant, rejecting unknown values
+pub fn generate_enum_deserialize(name: &syn::Ident, data_enum: &syn::DataEnum) -> proc_macro2::TokenStream {
+    let type_name = name.to_string();
     let variants = data_enum.variants.iter().map(|variant| {
         let variant_name = &variant.ident;
-        let discriminant = &variant.discriminant.as_ref().unwrap().1;
 
         quote! {
-            #discriminant => Ok(Self::#variant_name),
+            v if v == Self::#variant_name as i32 => Ok(Self::#variant_name),
         }
     });
 
@@ -28,9 +22,7 @@ pub fn generate_enum_deserialize_safe(data_enum: &syn::DataEnum) -> proc_macro2:
         let value = reader.read_i32()?;
         match value {
             #(#variants)*
-            _ => Err(memorypack::MemoryPackError::DeserializationError(
-                format!("Invalid discriminant {} for enum {}", value, stringify!(Self))
-            ))
+            _ => Err(memorypack::MemoryPackError::InvalidDiscriminant(#type_name, value))
         }
     }
 }
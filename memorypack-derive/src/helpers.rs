@@ -16,103 +16,365 @@ pub fn has_explicit_discriminants(data_enum: &syn::DataEnum) -> bool {
     data_enum.variants.iter().all(|v| v.discriminant.is_some())
 }
 
+/// Enums with any non-unit variant are encoded as unions
+#[inline]
+pub fn has_data_variants(data_enum: &syn::DataEnum) -> bool {
+    data_enum
+        .variants
+        .iter()
+        .any(|v| !matches!(v.fields, syn::Fields::Unit))
+}
+
 #[inline]
 pub fn should_skip_field(field: &Field) -> bool {
-    field.attrs.iter().any(|attr| {
-        attr.path().is_ident("memorypack")
-            && attr
-                .meta
-                .require_list()
-                .map(|m| {
-                    let tokens = m.tokens.to_string();
-                    tokens.contains("skip") || tokens.contains("ignore")
-                })
-                .unwrap_or(false)
-    }) || field
-        .ident
-        .as_ref()
-        .map(|ident| ident.to_string().starts_with('_'))
-        .unwrap_or(false)
+    has_field_flag(field, "skip")
+        || has_field_flag(field, "ignore")
+        || field
+            .ident
+            .as_ref()
+            .map(|ident| ident.to_string().starts_with('_'))
+            .unwrap_or(false)
 }
 
 pub fn get_field_order(field: &Field) -> Option<usize> {
-    field.attrs.iter().find_map(|attr| {
+    let value = field_attr_value(field, "order").ok()??;
+    parse_attr_value::<syn::LitInt>(value).ok()?.base10_parse().ok()
+}
+
+/// Whether a bare `flag` appears in the field's `#[memorypack(...)]` attributes; the values of
+/// `key = value` entries are skipped, so e.g. `default_with = "skip_all"` is not `skip`
+fn has_field_flag(field: &Field, flag: &str) -> bool {
+    let mut found = false;
+    for attr in field.attrs.iter().filter(|attr| attr.path().is_ident("memorypack")) {
+        // Malformed attributes are reported by the value parsers, so errors are ignored here
+        let _ = attr.parse_nested_meta(|meta| {
+            if meta.input.peek(syn::Token![=]) {
+                meta.value()?.parse::<syn::Expr>()?;
+            } else if meta.input.peek(syn::token::Paren) {
+                let _nested;
+                syn::parenthesized!(_nested in meta.input);
+            } else if meta.path.is_ident(flag) {
+                found = true;
+            }
+            Ok(())
+        });
+    }
+    found
+}
+
+/// Explicit union tag from `#[memorypack(tag = N)]`, `#[tag(N)]` or `#[tag = N]`
+pub fn get_variant_tag(variant: &syn::Variant) -> syn::Result<Option<u16>> {
+    use syn::punctuated::Punctuated;
+
+    fn parse_tag(expr: &syn::Expr) -> syn::Result<u16> {
+        match expr {
+            syn::Expr::Lit(syn::ExprLit { lit: syn::Lit::Int(lit), .. }) => lit
+                .base10_parse::<u16>()
+                .map_err(|_| syn::Error::new_spanned(lit, "Union tags must fit in a u16")),
+            _ => Err(syn::Error::new_spanned(expr, "Union tags must be integer literals")),
+        }
+    }
+
+    for attr in &variant.attrs {
+        if attr.path().is_ident("tag") {
+            return match &attr.meta {
+                syn::Meta::NameValue(meta) => parse_tag(&meta.value).map(Some),
+                _ => parse_tag(&attr.parse_args()?).map(Some),
+            };
+        }
+
+        if attr.path().is_ident("memorypack") {
+            let metas =
+                attr.parse_args_with(Punctuated::<syn::Meta, syn::Token![,]>::parse_terminated)?;
+            for meta in metas {
+                if let syn::Meta::NameValue(meta) = meta
+                    && meta.path.is_ident("tag")
+                {
+                    return parse_tag(&meta.value).map(Some);
+                }
+            }
+        }
+    }
+
+    Ok(None)
+}
+
+/// Value for a member missing from the payload: the explicit default or `Default::default()`
+pub fn field_default(field: &Field) -> proc_macro2::TokenStream {
+    field_explicit_default(field).unwrap_or_else(|| quote::quote! { Default::default() })
+}
+
+/// `#[memorypack(default = "expr")]` or `#[memorypack(default_with = "path")]`, if present
+pub fn field_explicit_default(field: &Field) -> Option<proc_macro2::TokenStream> {
+    use quote::{ToTokens, quote};
+
+    let default = field_attr_value(field, "default")
+        .and_then(|value| value.map(parse_attr_value::<syn::Expr>).transpose())
+        .and_then(|expr| match expr {
+            Some(expr) => Ok(Some(expr.into_token_stream())),
+            None => Ok(field_attr_value(field, "default_with")?
+                .map(parse_attr_value::<syn::Path>)
+                .transpose()?
+                .map(|path| quote! { #path() })),
+        });
+
+    default.unwrap_or_else(|err| Some(err.to_compile_error()))
+}
+
+/// Value of `key = value` in the field's `#[memorypack(...)]` attributes
+fn field_attr_value(field: &Field, key: &str) -> syn::Result<Option<syn::Expr>> {
+    use syn::punctuated::Punctuated;
+
+    for attr in &field.attrs {
         if !attr.path().is_ident("memorypack") {
-            return None;
+            continue;
+        }
+
+        let metas = attr.parse_args_with(Punctuated::<syn::Meta, syn::Token![,]>::parse_terminated)?;
+        for meta in metas {
+            if let syn::Meta::NameValue(meta) = meta
+                && meta.path.is_ident(key)
+            {
+                return Ok(Some(meta.value));
+            }
         }
+    }
+
+    Ok(None)
+}
 
-        let list = attr.meta.require_list().ok()?;
-        let tokens = list.tokens.to_string();
-        let order_pos = tokens.find("order")?;
-        let after_order = &tokens[order_pos..];
-        let eq_pos = after_order.find('=')?;
-        let after_eq = after_order[eq_pos + 1..].trim();
+/// Attribute values may be written bare or as a string, e.g. `default = 5` or `default = "5"`
+fn parse_attr_value<T: syn::parse::Parse>(value: syn::Expr) -> syn::Result<T> {
+    match value {
+        syn::Expr::Lit(syn::ExprLit { lit: syn::Lit::Str(lit), .. }) => lit.parse(),
+        value => syn::parse2(quote::ToTokens::into_token_stream(value)),
+    }
+}
 
-        let num_str = after_eq
-            .find(|c: char| !c.is_ascii_digit())
-            .map(|end| &after_eq[..end])
-            .unwrap_or(after_eq);
+/// Custom formatter function for one direction: `serialize_with`/`deserialize_with = "path"`,
+/// or `function` inside the module named by `with = "module"`
+fn field_formatter(field: &Field, key: &str, function: &str) -> syn::Result<Option<syn::Path>> {
+    if let Some(value) = field_attr_value(field, key)? {
+        return parse_attr_value(value).map(Some);
+    }
 
-        num_str.parse::<usize>().ok()
-    })
+    let Some(value) = field_attr_value(field, "with")? else {
+        return Ok(None);
+    };
+    let mut module: syn::Path = parse_attr_value(value)?;
+    module
+        .segments
+        .push(syn::Ident::new(function, proc_macro2::Span::call_site()).into());
+    Ok(Some(module))
 }
 
 #[inline]
 pub fn is_zero_copy_field(field: &Field) -> bool {
-    field.attrs.iter().any(|attr| {
-        attr.path().is_ident("memorypack")
-            && attr
-                .meta
-                .require_list()
-                .map(|m| m.tokens.to_string().contains("zero_copy"))
-                .unwrap_or(false)
-    })
+    has_field_flag(field, "zero_copy")
+}
+
+/// `MemoryPackFormatter` implementation named by `#[memorypack(formatter = "Type")]`
+fn field_formatter_type(field: &Field) -> syn::Result<Option<syn::Type>> {
+    field_attr_value(field, "formatter")?.map(parse_attr_value).transpose()
 }
 
 #[inline]
-pub fn is_borrowed_str(ty: &syn::Type) -> bool {
-    if let syn::Type::Reference(type_ref) = ty
-        && let syn::Type::Path(inner_path) = &*type_ref.elem
-    {
-        return inner_path.path.is_ident("str");
-    }
-    false
+pub fn is_nullable_object_field(field: &Field) -> bool {
+    has_field_flag(field, "nullable_object")
 }
 
 #[inline]
-pub fn is_borrowed_slice(ty: &syn::Type) -> bool {
-    if let syn::Type::Reference(type_ref) = ty {
-        return matches!(&*type_ref.elem, syn::Type::Slice(_));
+fn is_option(ty: &syn::Type) -> bool {
+    matches!(ty, syn::Type::Path(type_path)
+        if type_path.path.segments.last().is_some_and(|segment| segment.ident == "Option"))
+}
+
+fn nullable_object_error(field: &Field) -> proc_macro2::TokenStream {
+    syn::Error::new_spanned(&field.ty, "#[memorypack(nullable_object)] requires an Option<T> field")
+        .to_compile_error()
+}
+
+/// Serialize a field through `value`, a reference to it; custom formatters take precedence and
+/// `nullable_object` options write `None` as the null object marker
+pub fn field_serialize_call(
+    field: &Field,
+    value: proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    use quote::quote;
+
+    let ty = &field.ty;
+    match field_formatter_type(field) {
+        Ok(Some(formatter)) => {
+            return quote! {
+                <#formatter as memorypack::MemoryPackFormatter<#ty>>::serialize(#value, writer)?;
+            };
+        }
+        Ok(None) => {}
+        Err(err) => return err.to_compile_error(),
+    }
+    match field_formatter(field, "serialize_with", "serialize") {
+        Ok(Some(path)) => return quote! { #path(#value, writer)?; },
+        Ok(None) => {}
+        Err(err) => return err.to_compile_error(),
+    }
+    if !is_nullable_object_field(field) {
+        return quote! { memorypack::MemoryPackSerialize::serialize(#value, writer)?; };
+    }
+    if !is_option(&field.ty) {
+        return nullable_object_error(field);
+    }
+    quote! { memorypack::traits::serialize_nullable_object(#value, writer)?; }
+}
+
+/// Whether `lifetime` appears anywhere in `ty`, e.g. `Vec<&'a str>` or `Inner<'a>`
+pub fn type_mentions_lifetime(ty: &syn::Type, lifetime: &syn::Lifetime) -> bool {
+    fn walk(tokens: proc_macro2::TokenStream, lifetime: &syn::Lifetime) -> bool {
+        let mut after_quote = false;
+        tokens.into_iter().any(|token| match token {
+            proc_macro2::TokenTree::Punct(punct) => {
+                after_quote = punct.as_char() == '\'';
+                false
+            }
+            proc_macro2::TokenTree::Ident(ident) => {
+                let found = after_quote && ident == lifetime.ident;
+                after_quote = false;
+                found
+            }
+            proc_macro2::TokenTree::Group(group) => {
+                after_quote = false;
+                walk(group.stream(), lifetime)
+            }
+            proc_macro2::TokenTree::Literal(_) => {
+                after_quote = false;
+                false
+            }
+        })
+    }
+
+    walk(quote::ToTokens::to_token_stream(ty), lifetime)
+}
+
+/// Deserialize the member `name` into its value, adding `name` to the path of any error
+pub fn field_deserialize_call(
+    field: &Field,
+    name: &str,
+    borrowed_lifetime: Option<&syn::Lifetime>,
+) -> proc_macro2::TokenStream {
+    member_context(field_read_call(field, borrowed_lifetime), name)
+}
+
+/// `Result` expression reading a field through its custom formatter, or the zero-copy trait
+/// when it borrows from the input
+pub fn field_read_call(
+    field: &Field,
+    borrowed_lifetime: Option<&syn::Lifetime>,
+) -> proc_macro2::TokenStream {
+    use quote::quote;
+
+    let ty = &field.ty;
+    match field_formatter_type(field) {
+        Ok(Some(formatter)) => quote! { <#formatter as memorypack::MemoryPackFormatter<#ty>>::deserialize(reader) },
+        Ok(None) => match field_formatter(field, "deserialize_with", "deserialize") {
+            Ok(Some(path)) => quote! { #path(reader) },
+            Ok(None) => default_deserialize_call(field, borrowed_lifetime),
+            Err(err) => err.to_compile_error(),
+        },
+        Err(err) => err.to_compile_error(),
+    }
+}
+
+fn default_deserialize_call(
+    field: &Field,
+    borrowed_lifetime: Option<&syn::Lifetime>,
+) -> proc_macro2::TokenStream {
+    use quote::quote;
+
+    let borrows = borrowed_lifetime.is_some_and(|lifetime| type_mentions_lifetime(&field.ty, lifetime));
+    if is_nullable_object_field(field) {
+        if !is_option(&field.ty) {
+            return nullable_object_error(field);
+        }
+        return if borrows || is_zero_copy_field(field) {
+            quote! { memorypack::traits::deserialize_nullable_object_zero_copy(reader) }
+        } else {
+            quote! { memorypack::traits::deserialize_nullable_object(reader) }
+        };
+    }
+    if borrows || is_zero_copy_field(field) {
+        quote! { memorypack::MemoryPackDeserializeZeroCopy::deserialize(reader) }
+    } else {
+        quote! { memorypack::MemoryPackDeserialize::deserialize(reader) }
+    }
+}
+
+/// Propagate the error of `result`, a member read, with the member added to its path
+pub fn member_context(result: proc_macro2::TokenStream, name: &str) -> proc_macro2::TokenStream {
+    quote::quote! {
+        #result.map_err(|error| error.in_field(#name, reader.position()))?
+    }
+}
+
+/// Name of a member in error paths: its identifier, or its position in a tuple struct
+pub fn member_name(field: &Field, index: usize) -> String {
+    use syn::ext::IdentExt;
+
+    match &field.ident {
+        Some(ident) => ident.unraw().to_string(),
+        None => index.to_string(),
     }
-    false
 }
 
 pub fn is_option_box(ty: &syn::Type) -> bool {
+    generic_argument(ty, "Option").is_some_and(|inner| generic_argument(inner, "Box").is_some())
+}
+
+/// First type argument of `ty` when its last path segment is `wrapper`, e.g. `T` in `Option<T>`
+pub fn generic_argument<'a>(ty: &'a syn::Type, wrapper: &str) -> Option<&'a syn::Type> {
     let syn::Type::Path(type_path) = ty else {
-        return false;
-    };
-    let Some(segment) = type_path.path.segments.last() else {
-        return false;
+        return None;
     };
-
-    if segment.ident != "Option" {
-        return false;
+    let segment = type_path.path.segments.last()?;
+    if segment.ident != wrapper {
+        return None;
     }
 
     let syn::PathArguments::AngleBracketed(args) = &segment.arguments else {
-        return false;
-    };
-    let Some(syn::GenericArgument::Type(inner_ty)) = args.args.first() else {
-        return false;
+        return None;
     };
-    let syn::Type::Path(inner_path) = inner_ty else {
-        return false;
-    };
-    let Some(inner_segment) = inner_path.path.segments.last() else {
-        return false;
+    match args.args.first()? {
+        syn::GenericArgument::Type(inner) => Some(inner),
+        _ => None,
+    }
+}
+
+/// `&'static str` expression naming the custom formatter a field is written with: the
+/// `formatter` type, or the `serialize_with` function or `with` module path
+pub fn field_custom_format(field: &Field) -> syn::Result<Option<proc_macro2::TokenStream>> {
+    use quote::{ToTokens, quote};
+
+    if let Some(formatter) = field_formatter_type(field)? {
+        return Ok(Some(quote! { std::any::type_name::<#formatter>() }));
+    }
+    let value = match field_attr_value(field, "serialize_with")? {
+        Some(value) => value,
+        None => match field_attr_value(field, "with")? {
+            Some(value) => value,
+            None => return Ok(None),
+        },
     };
+    let path: syn::Path = parse_attr_value(value)?;
+    let path = path.to_token_stream().to_string().replace(' ', "");
+    Ok(Some(quote! { #path }))
+}
 
-    inner_segment.ident == "Box"
+/// Name given by `#[memorypack(schema = "Name")]`, describing a field whose type has no
+/// `MemoryPackSchema` impl as an opaque custom schema
+pub fn field_schema_override(field: &Field) -> syn::Result<Option<String>> {
+    match field_attr_value(field, "schema")? {
+        Some(syn::Expr::Lit(syn::ExprLit { lit: syn::Lit::Str(lit), .. })) => Ok(Some(lit.value())),
+        Some(value) => Err(syn::Error::new_spanned(value, "schema must be a string, e.g. schema = \"Vector3\"")),
+        None => Ok(None),
+    }
 }
 
 pub struct OrderedField<'a> {
@@ -134,37 +396,3 @@ pub fn prepare_ordered_fields<'a>(fields: &'a [&'a Field]) -> Vec<OrderedField<'
     ordered.sort_by_key(|f| f.order);
     ordered
 }
-
-pub fn generate_field_deserialize(
-    field: &Field,
-    is_zero_copy_struct: bool,
-) -> proc_macro2::TokenStream {
-    use quote::quote;
-
-    let name = &field.ident;
-    let ty = &field.ty;
-
-    if should_skip_field(field) {
-        return quote! {
-            let mut temp_reader = memorypack::MemoryPackReader::new(&[]);
-            let _: #ty = memorypack::MemoryPackDeserialize::deserialize(&mut temp_reader)?;
-            let #name = Default::default();
-        };
-    }
-
-    let is_field_zero_copy = is_zero_copy_field(field);
-
-    if is_zero_copy_struct || is_field_zero_copy {
-        if is_borrowed_str(ty) {
-            return quote! { let #name = reader.read_str()?; };
-        }
-        if is_borrowed_slice(ty) {
-            return quote! { let #name = reader.read_bytes()?; };
-        }
-        if is_field_zero_copy {
-            return quote! { let #name = memorypack::MemoryPackDeserializeZeroCopy::deserialize(reader)?; };
-        }
-    }
-
-    quote! { let #name = memorypack::MemoryPackDeserialize::deserialize(reader)?; }
-}
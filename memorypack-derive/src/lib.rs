@@ -6,19 +6,36 @@ mod attributes;
 mod circular;
 mod enums;
 mod helpers;
+mod nullable;
 mod regular;
+mod schema;
 mod unions;
+mod unmanaged;
 mod version_tolerant;
 
 use attributes::AttributeFlags;
 use circular::{generate_circular_deserialize, generate_circular_serialize};
 use enums::{
-    generate_enum_deserialize_safe, generate_enum_deserialize_unsafe, generate_enum_serialize,
-    generate_flags_impls, generate_transparent_deserialize, generate_transparent_serialize,
+    generate_enum_deserialize, generate_enum_serialize, generate_flags_impls,
+    generate_transparent_deserialize, generate_transparent_serialize,
+};
+use helpers::{has_data_variants, has_explicit_discriminants, is_single_field_i32};
+use nullable::{
+    generate_null_object_deserialize_hooks, generate_null_object_schema_hooks,
+    generate_null_object_serialize_hooks, generate_null_object_zero_copy_hooks,
 };
-use helpers::{has_explicit_discriminants, is_single_field_i32};
 use regular::{generate_deserialize, generate_serialize};
-use unions::{generate_union_deserialize, generate_union_serialize};
+use schema::{
+    SchemaLayout, generate_enum_schema, generate_object_schema, generate_transparent_schema,
+    generate_union_schema,
+};
+use unions::{
+    generate_implicit_tag_warning, generate_union_deserialize, generate_union_serialize, union_tags,
+};
+use unmanaged::{
+    generate_unmanaged_deserialize, generate_unmanaged_deserialize_hooks, generate_unmanaged_impl,
+    generate_unmanaged_serialize, generate_unmanaged_serialize_hooks,
+};
 use version_tolerant::{
     generate_version_tolerant_deserialize, generate_version_tolerant_serialize,
 };
@@ -31,11 +48,34 @@ pub fn derive_memorypack(input: TokenStream) -> TokenStream {
 
     let attrs = AttributeFlags::parse(&input.attrs);
 
+    if attrs.is_unmanaged {
+        if !matches!(input.data, Data::Struct(_)) || !attrs.has_repr_c {
+            return syn::Error::new_spanned(
+                &input,
+                "#[memorypack(unmanaged)] can only be applied to #[repr(C)] structs",
+            )
+            .to_compile_error()
+            .into();
+        }
+        if !input.generics.params.is_empty() {
+            return syn::Error::new_spanned(
+                &input.generics,
+                "#[memorypack(unmanaged)] structs cannot be generic",
+            )
+            .to_compile_error()
+            .into();
+        }
+    }
+
     let (serialize_impl, deserialize_impl) = match &input.data {
         Data::Struct(data_struct) if attrs.is_transparent && is_single_field_i32(data_struct) => (
             generate_transparent_serialize(),
             generate_transparent_deserialize(),
         ),
+        Data::Struct(_) if attrs.is_unmanaged => (
+            generate_unmanaged_serialize(&input.data),
+            generate_unmanaged_deserialize(&input.data),
+        ),
         Data::Struct(_) if attrs.is_circular => (
             generate_circular_serialize(&input.data, true),
             generate_circular_deserialize(&input.data, true),
@@ -46,12 +86,29 @@ pub fn derive_memorypack(input: TokenStream) -> TokenStream {
         ),
         Data::Struct(_) => (
             generate_serialize(&input.data),
-            generate_deserialize(&input.data, attrs.is_zero_copy),
-        ),
-        Data::Enum(data_enum) if attrs.is_union => (
-            generate_union_serialize(data_enum),
-            generate_union_deserialize(name, data_enum),
+            generate_deserialize(
+                name,
+                &input.data,
+                attrs.is_zero_copy.then(|| borrowed_lifetime(&input.generics)).flatten(),
+            ),
         ),
+        Data::Enum(data_enum) if attrs.is_union || has_data_variants(data_enum) => {
+            let tags = match union_tags(data_enum) {
+                Ok(tags) => tags,
+                Err(err) => return err.to_compile_error().into(),
+            };
+
+            (
+                generate_union_serialize(data_enum, &tags, attrs.is_version_tolerant),
+                generate_union_deserialize(
+                    name,
+                    data_enum,
+                    &tags,
+                    attrs.is_version_tolerant,
+                    attrs.is_zero_copy.then(|| borrowed_lifetime(&input.generics)).flatten(),
+                ),
+            )
+        }
         Data::Enum(data_enum) => {
             let has_explicit = has_explicit_discriminants(data_enum);
 
@@ -62,13 +119,7 @@ pub fn derive_memorypack(input: TokenStream) -> TokenStream {
                 ).to_compile_error().into();
             }
 
-            let deserialize = if has_explicit {
-                generate_enum_deserialize_safe(data_enum)
-            } else {
-                generate_enum_deserialize_unsafe()
-            };
-
-            (generate_enum_serialize(), deserialize)
+            (generate_enum_serialize(), generate_enum_deserialize(name, data_enum))
         }
         Data::Union(_) => {
             return syn::Error::new_spanned(
@@ -80,23 +131,121 @@ pub fn derive_memorypack(input: TokenStream) -> TokenStream {
         }
     };
 
+    let schema_impl = match &input.data {
+        Data::Struct(data_struct) if attrs.is_transparent && is_single_field_i32(data_struct) => {
+            generate_transparent_schema(name, &attrs)
+        }
+        Data::Struct(data_struct) => {
+            let layout = if attrs.is_unmanaged {
+                SchemaLayout::Unmanaged
+            } else if attrs.is_circular {
+                SchemaLayout::Circular
+            } else if attrs.is_version_tolerant {
+                SchemaLayout::VersionTolerant
+            } else {
+                SchemaLayout::Standard
+            };
+            generate_object_schema(name, &data_struct.fields, layout)
+        }
+        Data::Enum(data_enum) if attrs.is_union || has_data_variants(data_enum) => {
+            let variants: Vec<_> = data_enum.variants.iter().collect();
+            match union_tags(data_enum) {
+                Ok(tags) => generate_union_schema(name, &variants, &tags, attrs.is_version_tolerant),
+                Err(err) => return err.to_compile_error().into(),
+            }
+        }
+        Data::Enum(data_enum) => {
+            let variants: Vec<_> = data_enum.variants.iter().collect();
+            generate_enum_schema(name, &variants)
+        }
+        Data::Union(_) => quote! {},
+    };
+
     let flags_impl = if attrs.is_flags && attrs.is_transparent {
         generate_flags_impls(name)
     } else {
         quote! {}
     };
 
-    let zero_copy_impl = if attrs.is_zero_copy {
+    // Layouts starting with an object header or union tag can encode `None` as the null marker
+    let is_object = match &input.data {
+        Data::Struct(data_struct) if attrs.is_transparent && is_single_field_i32(data_struct) => false,
+        Data::Struct(_) if attrs.is_unmanaged => false,
+        Data::Struct(_) if attrs.is_circular || attrs.is_version_tolerant => true,
+        Data::Struct(data_struct) => !matches!(data_struct.fields, syn::Fields::Unit),
+        Data::Enum(data_enum) => attrs.is_union || has_data_variants(data_enum),
+        Data::Union(_) => false,
+    };
+
+    // Objects can nest recursively, so they count against the reader's depth limit, and
+    // name the type their members' error paths start from
+    let deserialize_impl = if is_object {
+        let type_name = name.to_string();
         quote! {
-            impl<'a> memorypack::MemoryPackDeserializeZeroCopy<'a> for #name<'a> {
-                #[inline]
-                fn deserialize(reader: &mut memorypack::MemoryPackReader<'a>) -> Result<Self, memorypack::MemoryPackError> {
-                    #deserialize_impl
-                }
-            }
+            reader
+                .nested(|reader| { #deserialize_impl })
+                .map_err(|error| error.in_type(#type_name, reader.position()))
         }
     } else {
-        quote! {}
+        deserialize_impl
+    };
+
+    let (zero_copy_lifetime, zero_copy_generics) = zero_copy_generics(&input.generics, attrs.is_zero_copy);
+
+    let (serialize_hooks, deserialize_hooks, zero_copy_hooks, unmanaged_impl) = if attrs.is_unmanaged {
+        (
+            generate_unmanaged_serialize_hooks(),
+            generate_unmanaged_deserialize_hooks(),
+            quote! {},
+            generate_unmanaged_impl(name, &input.data),
+        )
+    } else if is_object {
+        (
+            generate_null_object_serialize_hooks(),
+            generate_null_object_deserialize_hooks(),
+            generate_null_object_zero_copy_hooks(&zero_copy_lifetime),
+            quote! {},
+        )
+    } else {
+        (quote! {}, quote! {}, quote! {}, quote! {})
+    };
+
+    let schema_hooks = if is_object { generate_null_object_schema_hooks() } else { quote! {} };
+
+    // Fields of generic types are described through their parameters' schemas
+    let mut schema_generics = input.generics.clone();
+    for param in input.generics.type_params() {
+        let ident = &param.ident;
+        schema_generics
+            .make_where_clause()
+            .predicates
+            .push(syn::parse_quote! { #ident: memorypack::MemoryPackSchema });
+    }
+    let (_, _, schema_where_clause) = schema_generics.split_for_impl();
+
+    let union_warning = match &input.data {
+        Data::Enum(data_enum) if attrs.is_union || has_data_variants(data_enum) => {
+            generate_implicit_tag_warning(name, data_enum)
+        }
+        _ => quote! {},
+    };
+
+    // Owned types also implement the zero-copy trait so they can sit inside borrowed containers
+    let (zero_copy_impl_generics, _, _) = zero_copy_generics.split_for_impl();
+    let zero_copy_body = if attrs.is_zero_copy {
+        deserialize_impl.clone()
+    } else {
+        quote! { <Self as memorypack::MemoryPackDeserialize>::deserialize(reader) }
+    };
+    let zero_copy_impl = quote! {
+        impl #zero_copy_impl_generics memorypack::MemoryPackDeserializeZeroCopy<#zero_copy_lifetime> for #name #ty_generics #where_clause {
+            #[inline]
+            fn deserialize(reader: &mut memorypack::MemoryPackReader<#zero_copy_lifetime>) -> Result<Self, memorypack::MemoryPackError> {
+                #zero_copy_body
+            }
+
+            #zero_copy_hooks
+        }
     };
 
     let deserialize_regular_impl = if attrs.is_zero_copy {
@@ -108,6 +257,8 @@ pub fn derive_memorypack(input: TokenStream) -> TokenStream {
                 fn deserialize(reader: &mut memorypack::MemoryPackReader) -> Result<Self, memorypack::MemoryPackError> {
                     #deserialize_impl
                 }
+
+                #deserialize_hooks
             }
         }
     };
@@ -119,14 +270,48 @@ pub fn derive_memorypack(input: TokenStream) -> TokenStream {
                 #serialize_impl
                 Ok(())
             }
+
+            #serialize_hooks
         }
 
         #deserialize_regular_impl
 
         #zero_copy_impl
 
+        impl #impl_generics memorypack::MemoryPackSchema for #name #ty_generics #schema_where_clause {
+            fn schema() -> memorypack::schema::Schema {
+                #schema_impl
+            }
+
+            #schema_hooks
+        }
+
         #flags_impl
+
+        #unmanaged_impl
+
+        #union_warning
     };
 
     expanded.into()
 }
+
+/// The lifetime zero-copy fields borrow from: the type's first lifetime parameter
+fn borrowed_lifetime(generics: &syn::Generics) -> Option<&syn::Lifetime> {
+    generics.lifetimes().next().map(|param| &param.lifetime)
+}
+
+/// Generics for the `MemoryPackDeserializeZeroCopy` impl, adding a fresh input lifetime
+/// unless the type borrows from its own
+fn zero_copy_generics(generics: &syn::Generics, is_zero_copy: bool) -> (syn::Lifetime, syn::Generics) {
+    if is_zero_copy && let Some(lifetime) = borrowed_lifetime(generics) {
+        return (lifetime.clone(), generics.clone());
+    }
+
+    let lifetime = syn::Lifetime::new("'__memorypack_de", proc_macro2::Span::call_site());
+    let mut generics = generics.clone();
+    generics
+        .params
+        .insert(0, syn::GenericParam::Lifetime(syn::LifetimeParam::new(lifetime.clone())));
+    (lifetime, generics)
+}
@@ -0,0 +1,46 @@
+use quote::quote;
+
+// Derived objects map to C# reference types, so `Option<T>` writes `None` as the
+// null object marker instead of the `Nullable<T>` has-value flag
+
+pub fn generate_null_object_serialize_hooks() -> proc_macro2::TokenStream {
+    quote! {
+        #[inline]
+        fn serialize_option<F>(value: Option<&Self>, writer: &mut memorypack::MemoryPackWriter, _: F) -> Result<(), memorypack::MemoryPackError>
+        where
+            F: FnOnce(&mut memorypack::MemoryPackWriter) -> Result<(), memorypack::MemoryPackError>,
+        {
+            match value {
+                Some(value) => memorypack::MemoryPackSerialize::serialize(value, writer),
+                None => memorypack::traits::serialize_nullable_object::<Self>(&None, writer),
+            }
+        }
+    }
+}
+
+pub fn generate_null_object_deserialize_hooks() -> proc_macro2::TokenStream {
+    quote! {
+        #[inline]
+        fn deserialize_option(reader: &mut memorypack::MemoryPackReader) -> Result<Option<Self>, memorypack::MemoryPackError> {
+            memorypack::traits::deserialize_nullable_object(reader)
+        }
+    }
+}
+
+pub fn generate_null_object_zero_copy_hooks(lifetime: &syn::Lifetime) -> proc_macro2::TokenStream {
+    quote! {
+        #[inline]
+        fn deserialize_option(reader: &mut memorypack::MemoryPackReader<#lifetime>) -> Result<Option<Self>, memorypack::MemoryPackError> {
+            memorypack::traits::deserialize_nullable_object_zero_copy(reader)
+        }
+    }
+}
+
+pub fn generate_null_object_schema_hooks() -> proc_macro2::TokenStream {
+    quote! {
+        #[inline]
+        fn option_schema() -> memorypack::schema::Schema {
+            memorypack::schema::Schema::NullableReference(Box::new(<Self as memorypack::MemoryPackSchema>::schema()))
+        }
+    }
+}
@@ -1,4 +1,7 @@
-use crate::helpers::{generate_field_deserialize, prepare_ordered_fields, should_skip_field};
+use crate::helpers::{
+    field_default, field_deserialize_call, field_explicit_default, field_serialize_call, member_name,
+    prepare_ordered_fields, should_skip_field,
+};
 
 use quote::quote;
 use syn::{Data, Fields};
@@ -22,7 +25,7 @@ pub fn generate_serialize(data: &Data) -> proc_macro2::TokenStream {
 
             let serialize_fields = ordered.iter().map(|of| {
                 let name = of.ident;
-                quote! { memorypack::MemoryPackSerialize::serialize(&self.#name, writer)?; }
+                field_serialize_call(of.field, quote! { &self.#name })
             });
 
             quote! {
@@ -32,9 +35,9 @@ pub fn generate_serialize(data: &Data) -> proc_macro2::TokenStream {
         }
         Fields::Unnamed(fields) => {
             let field_count = fields.unnamed.len() as u8;
-            let serialize_fields = (0..fields.unnamed.len()).map(|i| {
+            let serialize_fields = fields.unnamed.iter().enumerate().map(|(i, field)| {
                 let index = syn::Index::from(i);
-                quote! { memorypack::MemoryPackSerialize::serialize(&self.#index, writer)?; }
+                field_serialize_call(field, quote! { &self.#index })
             });
 
             quote! {
@@ -46,13 +49,23 @@ pub fn generate_serialize(data: &Data) -> proc_macro2::TokenStream {
     }
 }
 
-pub fn generate_deserialize(data: &Data, is_zero_copy: bool) -> proc_macro2::TokenStream {
+pub fn generate_deserialize(
+    name: &syn::Ident,
+    data: &Data,
+    borrowed_lifetime: Option<&syn::Lifetime>,
+) -> proc_macro2::TokenStream {
+    let type_name = name.to_string();
+
     let Data::Struct(data_struct) = data else {
         return quote! {
             compile_error!("MemoryPackable deserialize can only be derived for structs");
         };
     };
 
+    // Members past the header's count are missing from older payloads and get their explicit
+    // default or `Default::default()`, erroring only for types without one (including type
+    // parameters not bounded by `Default`). Headers with more members than the type has cannot
+    // be read, as their lengths are unknown
     match &data_struct.fields {
         Fields::Named(fields) => {
             let non_skip: Vec<_> = fields
@@ -64,35 +77,27 @@ pub fn generate_deserialize(data: &Data, is_zero_copy: bool) -> proc_macro2::Tok
 
             let all_field_names: Vec<_> = fields.named.iter().map(|f| &f.ident).collect();
 
-            let deserialize_stmts: Vec<_> = fields
-                .named
-                .iter()
-                .map(|f| generate_field_deserialize(f, is_zero_copy))
-                .collect();
-
-            let mut ordered_deserialize = Vec::new();
-            let mut skip_field_idx = 0;
-            let mut ordered_idx = 0;
-
-            for f in &fields.named {
-                if should_skip_field(f) {
-                    ordered_deserialize
-                        .push(deserialize_stmts[skip_field_idx + ordered_idx].clone());
-                    skip_field_idx += 1;
-                } else if ordered_idx < ordered.len() {
-                    let field_idx = fields
-                        .named
-                        .iter()
-                        .position(|field| std::ptr::eq(field, ordered[ordered_idx].field))
-                        .unwrap();
-                    ordered_deserialize.push(deserialize_stmts[field_idx].clone());
-                    ordered_idx += 1;
+            let total = ordered.len();
+            let deserialize_fields = ordered.iter().enumerate().map(|(position, of)| {
+                let name = of.ident;
+                let deserialize = field_deserialize_call(of.field, &member_name(of.field, position), borrowed_lifetime);
+                let missing = missing_member(of.field, &type_name, total);
+                quote! {
+                    let #name = if #position < member_count { #deserialize } else { #missing };
                 }
-            }
+            });
+            let skipped_fields = fields.named.iter().filter(|f| should_skip_field(f)).map(|f| {
+                let name = &f.ident;
+                let default = field_default(f);
+                quote! { let #name = #default; }
+            });
+
+            let read_member_count = read_member_count(&type_name, total);
 
             quote! {
-                let _header = reader.read_u8()?;
-                #(#ordered_deserialize)*
+                #read_member_count
+                #(#deserialize_fields)*
+                #(#skipped_fields)*
                 Ok(Self { #(#all_field_names),* })
             }
         }
@@ -102,12 +107,18 @@ pub fn generate_deserialize(data: &Data, is_zero_copy: bool) -> proc_macro2::Tok
                 .map(|i| syn::Ident::new(&format!("field_{}", i), proc_macro2::Span::call_site()))
                 .collect();
 
-            let deserialize_stmts = field_vars.iter().map(|var| {
-                quote! { let #var = memorypack::MemoryPackDeserialize::deserialize(reader)?; }
+            let deserialize_stmts = fields.unnamed.iter().zip(&field_vars).enumerate().map(|(position, (field, var))| {
+                let deserialize = field_deserialize_call(field, &member_name(field, position), borrowed_lifetime);
+                let missing = missing_member(field, &type_name, len);
+                quote! {
+                    let #var = if #position < member_count { #deserialize } else { #missing };
+                }
             });
 
+            let read_member_count = read_member_count(&type_name, len);
+
             quote! {
-                let _header = reader.read_u8()?;
+                #read_member_count
                 #(#deserialize_stmts)*
                 Ok(Self(#(#field_vars),*))
             }
@@ -115,3 +126,23 @@ pub fn generate_deserialize(data: &Data, is_zero_copy: bool) -> proc_macro2::Tok
         Fields::Unit => quote! { Ok(Self) },
     }
 }
+
+/// Read the object header into `member_count`, rejecting more members than the type has
+pub fn read_member_count(type_name: &str, total: usize) -> proc_macro2::TokenStream {
+    quote! {
+        let member_count = reader.read_u8()? as usize;
+        if member_count > #total {
+            return Err(memorypack::MemoryPackError::MemberCountMismatch(#type_name, #total, member_count));
+        }
+    }
+}
+
+fn missing_member(field: &syn::Field, type_name: &str, total: usize) -> proc_macro2::TokenStream {
+    let ty = &field.ty;
+    field_explicit_default(field).unwrap_or_else(|| {
+        quote! {{
+            use memorypack::traits::{DefaultMember as _, RequiredMember as _};
+            (&memorypack::traits::MissingMember::<#ty>::new()).fill(#type_name, #total, member_count)?
+        }}
+    })
+}
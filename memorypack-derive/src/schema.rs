@@ -0,0 +1,248 @@
+use crate::attributes::AttributeFlags;
+use crate::helpers::{
+    field_custom_format, field_schema_override, generic_argument, is_nullable_object_field,
+    is_option_box, member_name, prepare_ordered_fields, should_skip_field,
+};
+
+use quote::{ToTokens, quote};
+use syn::{Fields, Variant};
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum SchemaLayout {
+    Standard,
+    VersionTolerant,
+    Circular,
+    Unmanaged,
+}
+
+impl SchemaLayout {
+    fn tokens(self) -> proc_macro2::TokenStream {
+        match self {
+            Self::Standard => quote! { memorypack::schema::ObjectLayout::Standard },
+            Self::VersionTolerant => quote! { memorypack::schema::ObjectLayout::VersionTolerant },
+            Self::Circular => quote! { memorypack::schema::ObjectLayout::Circular },
+            Self::Unmanaged => quote! { memorypack::schema::ObjectLayout::Unmanaged },
+        }
+    }
+}
+
+/// Describe a struct written as an object, wrapped so self-referencing types terminate
+pub fn generate_object_schema(
+    name: &syn::Ident,
+    fields: &Fields,
+    layout: SchemaLayout,
+) -> proc_macro2::TokenStream {
+    if layout == SchemaLayout::Standard && matches!(fields, Fields::Unit) {
+        return quote! { memorypack::schema::Schema::Unit };
+    }
+
+    let object = object_schema(&name.to_string(), fields, layout);
+    describe(name, quote! { memorypack::schema::Schema::Object(#object) })
+}
+
+/// Describe a union; delegating variants take their inner type's schema, the others are objects
+/// named `{Union}{Variant}`
+pub fn generate_union_schema(
+    name: &syn::Ident,
+    variants: &[&Variant],
+    tags: &[u16],
+    version_tolerant: bool,
+) -> proc_macro2::TokenStream {
+    let layout = if version_tolerant { SchemaLayout::VersionTolerant } else { SchemaLayout::Standard };
+    let variants = variants.iter().zip(tags).map(|(variant, &tag)| {
+        let variant_name = variant.ident.to_string();
+        let schema = match &variant.fields {
+            Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+                field_schema(&fields.unnamed[0], SchemaLayout::Standard)
+            }
+            fields => {
+                let object = object_schema(&format!("{name}{variant_name}"), fields, layout);
+                quote! { memorypack::schema::Schema::Object(#object) }
+            }
+        };
+        quote! {
+            memorypack::schema::UnionVariant { name: #variant_name, tag: #tag, schema: #schema }
+        }
+    });
+
+    let type_name = name.to_string();
+    describe(
+        name,
+        quote! {
+            memorypack::schema::Schema::Union(memorypack::schema::UnionSchema {
+                name: #type_name,
+                variants: vec![#(#variants),*],
+            })
+        },
+    )
+}
+
+/// Describe a C-like enum by the `i32` value of each variant
+pub fn generate_enum_schema(name: &syn::Ident, variants: &[&Variant]) -> proc_macro2::TokenStream {
+    let type_name = name.to_string();
+    let variants = variants.iter().map(|variant| {
+        let variant_ident = &variant.ident;
+        let variant_name = variant_ident.to_string();
+        quote! {
+            memorypack::schema::EnumVariant { name: #variant_name, value: Self::#variant_ident as i32 as i64 }
+        }
+    });
+
+    quote! {
+        memorypack::schema::Schema::Enum(memorypack::schema::EnumSchema {
+            name: #type_name,
+            underlying: memorypack::schema::Primitive::I32,
+            flags: false,
+            variants: vec![#(#variants),*],
+        })
+    }
+}
+
+/// Describe a `repr(transparent)` `i32` wrapper as an enum without named values
+pub fn generate_transparent_schema(name: &syn::Ident, attrs: &AttributeFlags) -> proc_macro2::TokenStream {
+    let type_name = name.to_string();
+    let flags = attrs.is_flags;
+    quote! {
+        memorypack::schema::Schema::Enum(memorypack::schema::EnumSchema {
+            name: #type_name,
+            underlying: memorypack::schema::Primitive::I32,
+            flags: #flags,
+            variants: Vec::new(),
+        })
+    }
+}
+
+fn describe(name: &syn::Ident, schema: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+    let type_name = name.to_string();
+    quote! {
+        memorypack::schema::describe(std::any::type_name::<Self>(), #type_name, || #schema)
+    }
+}
+
+/// Members in wire order, mirroring which fields each layout skips and reorders
+fn object_schema(name: &str, fields: &Fields, layout: SchemaLayout) -> proc_macro2::TokenStream {
+    let all: Vec<_> = fields.iter().collect();
+
+    // Tuple structs ignore `skip` and `order` unless they are version-tolerant
+    let keeps_all = layout == SchemaLayout::Unmanaged
+        || (matches!(fields, Fields::Unnamed(_)) && layout != SchemaLayout::VersionTolerant);
+
+    let (members, skipped) = if keeps_all {
+        let members: Vec<_> = all
+            .iter()
+            .enumerate()
+            .map(|(index, field)| member_schema(field, &member_name(field, index), index, layout))
+            .collect();
+        (members, Vec::new())
+    } else {
+        let non_skip: Vec<_> = all.iter().copied().filter(|f| !should_skip_field(f)).collect();
+        let members = prepare_ordered_fields(&non_skip)
+            .iter()
+            .enumerate()
+            .map(|(position, of)| {
+                let index = all.iter().position(|f| std::ptr::eq(*f, of.field)).unwrap();
+                // Standard objects write their members back to back, so only the position counts
+                let order = if layout == SchemaLayout::Standard { position } else { of.order };
+                member_schema(of.field, &member_name(of.field, index), order, layout)
+            })
+            .collect();
+        let skipped = all
+            .iter()
+            .enumerate()
+            .filter(|(_, f)| should_skip_field(f))
+            .map(|(index, f)| member_name(f, index))
+            .collect();
+        (members, skipped)
+    };
+
+    let layout = layout.tokens();
+    quote! {
+        memorypack::schema::ObjectSchema {
+            name: #name,
+            layout: #layout,
+            members: vec![#(#members),*],
+            skipped: vec![#(#skipped),*],
+        }
+    }
+}
+
+fn member_schema(
+    field: &syn::Field,
+    name: &str,
+    order: usize,
+    layout: SchemaLayout,
+) -> proc_macro2::TokenStream {
+    let type_name = type_name(&field.ty);
+    let schema = field_schema(field, layout);
+    quote! {
+        memorypack::schema::MemberSchema {
+            name: #name,
+            order: #order,
+            type_name: #type_name,
+            schema: #schema,
+        }
+    }
+}
+
+/// Schema of a field as it is written: `schema = "Name"` overrides, custom formatters and types
+/// without a `MemoryPackSchema` impl are opaque, and `nullable_object` options and circular
+/// `Option<Box<T>>` members use the null object marker
+fn field_schema(field: &syn::Field, layout: SchemaLayout) -> proc_macro2::TokenStream {
+    match field_schema_override(field) {
+        Ok(Some(name)) => return quote! { memorypack::schema::Schema::Custom(#name) },
+        Ok(None) => {}
+        Err(err) => return err.to_compile_error(),
+    }
+    match field_custom_format(field) {
+        Ok(Some(format)) => return quote! { memorypack::schema::Schema::Custom(#format) },
+        Ok(None) => {}
+        Err(err) => return err.to_compile_error(),
+    }
+
+    let nullable = if layout == SchemaLayout::Circular && is_option_box(&field.ty) {
+        generic_argument(&field.ty, "Option").and_then(|boxed| generic_argument(boxed, "Box"))
+    } else if is_nullable_object_field(field) {
+        generic_argument(&field.ty, "Option")
+    } else {
+        None
+    };
+
+    match nullable {
+        Some(inner) => {
+            let inner_schema = described_schema(inner);
+            quote! { memorypack::schema::Schema::NullableReference(Box::new(#inner_schema)) }
+        }
+        None => described_schema(&field.ty),
+    }
+}
+
+/// `ty`'s own schema, or an opaque one named after it if it has no `MemoryPackSchema` impl
+fn described_schema(ty: &syn::Type) -> proc_macro2::TokenStream {
+    let name = type_name(ty);
+    quote! {{
+        use memorypack::schema::{DescribedField as _, OpaqueField as _};
+        (&memorypack::schema::FieldSchema::<#ty>::new()).field_schema(#name)
+    }}
+}
+
+/// The type as written in the source, without the spaces token printing adds around punctuation
+fn type_name(ty: &syn::Type) -> String {
+    let tokens = ty.to_token_stream().to_string();
+    let is_word = |c: char| c.is_alphanumeric() || c == '_';
+
+    let mut name = String::with_capacity(tokens.len());
+    let mut chars = tokens.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == ' ' {
+            let before = name.chars().last();
+            let after = chars.peek().copied();
+            let between_words = before.is_some_and(is_word) && after.is_some_and(is_word);
+            if between_words || matches!(before, Some(',' | ';')) {
+                name.push(' ');
+            }
+            continue;
+        }
+        name.push(c);
+    }
+    name
+}
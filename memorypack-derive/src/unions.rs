@@ -1,27 +1,241 @@
-use quote::quote;
-use syn::Fields;
+use crate::helpers::{
+    field_default, field_deserialize_call, field_read_call, field_serialize_call, get_variant_tag,
+    member_name, prepare_ordered_fields, should_skip_field,
+};
+use crate::version_tolerant::{
+    generate_members_deserialize, generate_members_serialize, member_binding,
+};
 
-pub fn generate_union_serialize(data_enum: &syn::DataEnum) -> proc_macro2::TokenStream {
-    for variant in &data_enum.variants {
-        match &variant.fields {
-            Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {}
-            _ => {
-                return quote! {
-                    compile_error!("Union variants must have exactly one unnamed field");
-                };
-            }
+use quote::{format_ident, quote, quote_spanned};
+use syn::{Fields, Variant};
+
+/// Tags from 250 up are written as the marker byte followed by a `u16`, like C# `ushort` tags
+const WIDE_TAG: u16 = 250;
+
+// Variant layouts:
+// - `V(T)` delegates to `T`, matching C# unions over generated classes
+// - `V` is a zero-member object
+// - `V { .. }` and `V(A, B, ..)` are objects with their fields as members
+// - under `version_tolerant`, every non-delegating variant is a version-tolerant object
+
+#[inline]
+fn is_delegating(variant: &Variant) -> bool {
+    matches!(&variant.fields, Fields::Unnamed(fields) if fields.unnamed.len() == 1)
+}
+
+/// Resolve each variant's tag, defaulting to its position when no explicit tag is given
+pub fn union_tags(data_enum: &syn::DataEnum) -> syn::Result<Vec<u16>> {
+    let mut tags: Vec<u16> = Vec::with_capacity(data_enum.variants.len());
+
+    for (index, variant) in data_enum.variants.iter().enumerate() {
+        let tag = match get_variant_tag(variant)? {
+            Some(tag) => tag,
+            None => u16::try_from(index).map_err(|_| {
+                syn::Error::new_spanned(variant, "Unions can have at most 65536 variants")
+            })?,
+        };
+
+        if let Some(previous) = tags.iter().position(|&t| t == tag) {
+            return Err(syn::Error::new_spanned(
+                &variant.ident,
+                format!(
+                    "Duplicate union tag {} (already used by `{}`)",
+                    tag, data_enum.variants[previous].ident
+                ),
+            ));
         }
+        tags.push(tag);
     }
 
-    let variants = data_enum.variants.iter().enumerate().map(|(tag, variant)| {
-        let variant_name = &variant.ident;
-        let tag_value = tag as u8;
+    Ok(tags)
+}
+
+/// Deprecation warning for unions relying on positional tags, which break when variants are reordered
+pub fn generate_implicit_tag_warning(
+    name: &syn::Ident,
+    data_enum: &syn::DataEnum,
+) -> proc_macro2::TokenStream {
+    let Some(variant) = data_enum
+        .variants
+        .iter()
+        .find(|v| matches!(get_variant_tag(v), Ok(None)))
+    else {
+        return quote! {};
+    };
 
+    let note = format!(
+        "variant `{}::{}` has no explicit tag; add #[memorypack(tag = N)] to every variant \
+         so reordering variants cannot change the wire format",
+        name, variant.ident
+    );
+    let marker = format_ident!("implicit_union_tags_for_{}", name);
+
+    quote_spanned! {variant.ident.span()=>
+        const _: () = {
+            #[deprecated(note = #note)]
+            #[allow(non_camel_case_types)]
+            struct #marker;
+            let _ = #marker;
+        };
+    }
+}
+
+fn write_tag(tag: u16) -> proc_macro2::TokenStream {
+    if tag < WIDE_TAG {
+        let tag = tag as u8;
+        quote! { writer.write_u8(#tag)?; }
+    } else {
+        let marker = WIDE_TAG as u8;
         quote! {
-            Self::#variant_name(inner) => {
-                writer.write_u8(#tag_value)?;
-                memorypack::MemoryPackSerialize::serialize(inner, writer)?;
+            writer.write_u8(#marker)?;
+            writer.write_u16(#tag)?;
+        }
+    }
+}
+
+fn generate_version_tolerant_variant_serialize(
+    variant: &Variant,
+    write_tag: proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    let variant_name = &variant.ident;
+    let fields: Vec<_> = variant.fields.iter().collect();
+
+    let pattern = match &variant.fields {
+        Fields::Named(_) => {
+            let bindings = fields.iter().enumerate().filter(|(_, f)| !should_skip_field(f)).map(|(i, f)| {
+                let name = &f.ident;
+                let var = member_binding(i);
+                quote! { #name: #var }
+            });
+            quote! { Self::#variant_name { #(#bindings,)* .. } }
+        }
+        Fields::Unnamed(_) => {
+            let bindings = fields.iter().enumerate().map(|(i, f)| {
+                if should_skip_field(f) {
+                    quote! { _ }
+                } else {
+                    let var = member_binding(i);
+                    quote! { #var }
+                }
+            });
+            quote! { Self::#variant_name(#(#bindings),*) }
+        }
+        Fields::Unit => quote! { Self::#variant_name },
+    };
+    let serialize_members = generate_members_serialize(&fields, |i| {
+        let var = member_binding(i);
+        quote! { #var }
+    });
+
+    quote! {
+        #pattern => {
+            #write_tag
+            #serialize_members
+        }
+    }
+}
+
+fn generate_version_tolerant_variant_deserialize(variant: &Variant) -> proc_macro2::TokenStream {
+    let variant_name = &variant.ident;
+    let fields: Vec<_> = variant.fields.iter().collect();
+    let deserialize_members = generate_members_deserialize(&fields);
+    let vars: Vec<_> = (0..fields.len()).map(member_binding).collect();
+
+    let construct = match &variant.fields {
+        Fields::Named(_) => {
+            let names = fields.iter().map(|f| &f.ident);
+            quote! { Self::#variant_name { #(#names: #vars),* } }
+        }
+        Fields::Unnamed(_) => quote! { Self::#variant_name(#(#vars),*) },
+        Fields::Unit => quote! { Self::#variant_name },
+    };
+
+    quote! {
+        #deserialize_members
+        Ok(#construct)
+    }
+}
+
+/// Read a variant object's header, which must list every member of the variant
+fn read_variant_member_count(label: &str, count: usize) -> proc_macro2::TokenStream {
+    quote! {
+        let member_count = reader.read_u8()? as usize;
+        if member_count != #count {
+            return Err(memorypack::MemoryPackError::MemberCountMismatch(#label, #count, member_count));
+        }
+    }
+}
+
+pub fn generate_union_serialize(
+    data_enum: &syn::DataEnum,
+    tags: &[u16],
+    version_tolerant: bool,
+) -> proc_macro2::TokenStream {
+    let variants = data_enum.variants.iter().zip(tags).map(|(variant, &tag)| {
+        let variant_name = &variant.ident;
+        let write_tag = write_tag(tag);
+
+        match &variant.fields {
+            Fields::Unnamed(fields) if is_delegating(variant) => {
+                let serialize = field_serialize_call(&fields.unnamed[0], quote! { inner });
+                quote! {
+                    Self::#variant_name(inner) => {
+                        #write_tag
+                        #serialize
+                    }
+                }
+            }
+            _ if version_tolerant => generate_version_tolerant_variant_serialize(variant, write_tag),
+            Fields::Named(fields) => {
+                let non_skip: Vec<_> = fields
+                    .named
+                    .iter()
+                    .filter(|f| !should_skip_field(f))
+                    .collect();
+                let ordered = prepare_ordered_fields(&non_skip);
+                let field_count = ordered.len() as u8;
+
+                let bindings = ordered.iter().enumerate().map(|(i, of)| {
+                    let name = of.ident;
+                    let var = member_binding(i);
+                    quote! { #name: #var }
+                });
+                let serialize_fields = ordered.iter().enumerate().map(|(i, of)| {
+                    let var = member_binding(i);
+                    field_serialize_call(of.field, quote! { #var })
+                });
+
+                quote! {
+                    Self::#variant_name { #(#bindings,)* .. } => {
+                        #write_tag
+                        writer.write_u8(#field_count)?;
+                        #(#serialize_fields)*
+                    }
+                }
             }
+            Fields::Unnamed(fields) => {
+                let field_count = fields.unnamed.len() as u8;
+                let vars: Vec<_> = (0..fields.unnamed.len()).map(member_binding).collect();
+                let serialize_fields = fields
+                    .unnamed
+                    .iter()
+                    .zip(&vars)
+                    .map(|(field, var)| field_serialize_call(field, quote! { #var }));
+
+                quote! {
+                    Self::#variant_name(#(#vars),*) => {
+                        #write_tag
+                        writer.write_u8(#field_count)?;
+                        #(#serialize_fields)*
+                    }
+                }
+            }
+            Fields::Unit => quote! {
+                Self::#variant_name => {
+                    #write_tag
+                    writer.write_u8(0)?;
+                }
+            },
         }
     });
 
@@ -35,26 +249,96 @@ pub fn generate_union_serialize(data_enum: &syn::DataEnum) -> proc_macro2::Token
 pub fn generate_union_deserialize(
     name: &syn::Ident,
     data_enum: &syn::DataEnum,
+    tags: &[u16],
+    version_tolerant: bool,
+    borrowed_lifetime: Option<&syn::Lifetime>,
 ) -> proc_macro2::TokenStream {
-    let variants = data_enum.variants.iter().enumerate().map(|(tag, variant)| {
+    let wide_tag = WIDE_TAG as u8;
+    let type_name = name.to_string();
+    let variants = data_enum.variants.iter().zip(tags).map(|(variant, &tag_value)| {
         let variant_name = &variant.ident;
-        let tag_value = tag as u8;
+        let label = format!("{}::{}", name, variant_name);
 
-        quote! {
-            #tag_value => {
-                let inner = memorypack::MemoryPackDeserialize::deserialize(reader)?;
-                Ok(Self::#variant_name(inner))
+        let body = match &variant.fields {
+            Fields::Unnamed(fields) if is_delegating(variant) => {
+                let deserialize = field_read_call(&fields.unnamed[0], borrowed_lifetime);
+                quote! {
+                    Ok(Self::#variant_name(#deserialize?))
+                }
+            }
+            _ if version_tolerant => generate_version_tolerant_variant_deserialize(variant),
+            Fields::Named(fields) => {
+                let non_skip: Vec<_> = fields
+                    .named
+                    .iter()
+                    .filter(|f| !should_skip_field(f))
+                    .collect();
+                let ordered = prepare_ordered_fields(&non_skip);
+                let read_member_count = read_variant_member_count(&label, ordered.len());
+
+                let deserialize_fields = ordered.iter().map(|of| {
+                    let name = of.ident;
+                    let deserialize = field_deserialize_call(of.field, &member_name(of.field, 0), borrowed_lifetime);
+                    quote! { let #name = #deserialize; }
+                });
+                let field_inits = fields.named.iter().map(|f| {
+                    let name = &f.ident;
+                    if should_skip_field(f) {
+                        let default = field_default(f);
+                        quote! { #name: #default }
+                    } else {
+                        quote! { #name }
+                    }
+                });
+
+                quote! {
+                    #read_member_count
+                    #(#deserialize_fields)*
+                    Ok(Self::#variant_name { #(#field_inits),* })
+                }
+            }
+            Fields::Unnamed(fields) => {
+                let read_member_count = read_variant_member_count(&label, fields.unnamed.len());
+                let vars: Vec<_> = (0..fields.unnamed.len()).map(member_binding).collect();
+                let deserialize_fields = fields.unnamed.iter().zip(&vars).enumerate().map(|(i, (field, var))| {
+                    let deserialize = field_deserialize_call(field, &member_name(field, i), borrowed_lifetime);
+                    quote! { let #var = #deserialize; }
+                });
+
+                quote! {
+                    #read_member_count
+                    #(#deserialize_fields)*
+                    Ok(Self::#variant_name(#(#vars),*))
+                }
             }
+            Fields::Unit => {
+                let read_member_count = read_variant_member_count(&label, 0);
+                quote! {
+                    #read_member_count
+                    Ok(Self::#variant_name)
+                }
+            }
+        };
+
+        let variant_label = variant_name.to_string();
+        quote! {
+            #tag_value => reader.variant(#variant_label, |reader| { #body }),
         }
     });
 
     quote! {
-        let tag = reader.read_u8()?;
+        let tag = match reader.read_u8()? {
+            #wide_tag => reader.read_u16()?,
+            255 => {
+                return Err(memorypack::MemoryPackError::DeserializationError(
+                    format!("Null union for {}; nullable unions must be read as an Option", stringify!(#name))
+                ));
+            }
+            tag => tag as u16,
+        };
         match tag {
             #(#variants)*
-            _ => Err(memorypack::MemoryPackError::DeserializationError(
-                format!("Unknown union tag {} for {}", tag, stringify!(#name))
-            ))
+            _ => Err(memorypack::MemoryPackError::UnknownUnionTag(#type_name, tag))
         }
     }
 }
@@ -0,0 +1,111 @@
+use quote::quote;
+use syn::{Data, Fields};
+
+fn struct_fields(data: &Data) -> Option<&Fields> {
+    match data {
+        Data::Struct(data_struct) => Some(&data_struct.fields),
+        _ => None,
+    }
+}
+
+pub fn generate_unmanaged_serialize(data: &Data) -> proc_macro2::TokenStream {
+    let Some(fields) = struct_fields(data) else {
+        return quote! {};
+    };
+
+    let serialize_fields = fields.members().map(|member| {
+        quote! { memorypack::MemoryPackSerialize::serialize(&self.#member, writer)?; }
+    });
+
+    quote! {
+        if cfg!(target_endian = "little") {
+            memorypack::traits::write_unmanaged(self, writer)?;
+        } else {
+            #(#serialize_fields)*
+        }
+    }
+}
+
+pub fn generate_unmanaged_deserialize(data: &Data) -> proc_macro2::TokenStream {
+    let Some(fields) = struct_fields(data) else {
+        return quote! {};
+    };
+
+    let members: Vec<_> = fields.members().collect();
+    let construct = match fields {
+        Fields::Named(_) => quote! {
+            Self { #(#members: memorypack::MemoryPackDeserialize::deserialize(reader)?),* }
+        },
+        Fields::Unnamed(_) => {
+            let values = members.iter().map(|_| {
+                quote! { memorypack::MemoryPackDeserialize::deserialize(reader)? }
+            });
+            quote! { Self(#(#values),*) }
+        }
+        Fields::Unit => quote! { Self },
+    };
+
+    quote! {
+        if cfg!(target_endian = "little") {
+            memorypack::traits::read_unmanaged(reader)
+        } else {
+            Ok(#construct)
+        }
+    }
+}
+
+pub fn generate_unmanaged_serialize_hooks() -> proc_macro2::TokenStream {
+    quote! {
+        #[cfg(target_endian = "little")]
+        #[inline]
+        fn serialize_slice(items: &[Self], writer: &mut memorypack::MemoryPackWriter) -> Result<(), memorypack::MemoryPackError> {
+            memorypack::traits::write_unmanaged_slice(items, writer)
+        }
+    }
+}
+
+pub fn generate_unmanaged_deserialize_hooks() -> proc_macro2::TokenStream {
+    quote! {
+        #[cfg(target_endian = "little")]
+        #[inline]
+        fn deserialize_array<const N: usize>(
+            reader: &mut memorypack::MemoryPackReader,
+        ) -> Result<[Self; N], memorypack::MemoryPackError> {
+            memorypack::traits::read_unmanaged_array(reader)
+        }
+
+        #[cfg(target_endian = "little")]
+        #[inline]
+        fn deserialize_vec(
+            reader: &mut memorypack::MemoryPackReader,
+            len: usize,
+        ) -> Result<Vec<Self>, memorypack::MemoryPackError> {
+            memorypack::traits::read_unmanaged_vec(reader, len)
+        }
+    }
+}
+
+/// Implements `MemoryPackUnmanaged` after checking at compile time that every field is
+/// unmanaged and that the `#[repr(C)]` layout has no padding
+pub fn generate_unmanaged_impl(name: &syn::Ident, data: &Data) -> proc_macro2::TokenStream {
+    let Some(fields) = struct_fields(data) else {
+        return quote! {};
+    };
+
+    let field_types: Vec<_> = fields.iter().map(|f| &f.ty).collect();
+
+    quote! {
+        const _: () = {
+            fn assert_unmanaged<T: memorypack::MemoryPackUnmanaged>() {}
+            fn assert_fields() {
+                #(assert_unmanaged::<#field_types>();)*
+            }
+            assert!(
+                std::mem::size_of::<#name>() == 0 #(+ std::mem::size_of::<#field_types>())*,
+                concat!("#[memorypack(unmanaged)] struct ", stringify!(#name), " must not contain padding"),
+            );
+        };
+
+        unsafe impl memorypack::MemoryPackUnmanaged for #name {}
+    }
+}
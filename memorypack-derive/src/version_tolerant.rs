@@ -1,7 +1,13 @@
-use crate::helpers::{prepare_ordered_fields, should_skip_field};
+use crate::helpers::{
+    field_default, field_deserialize_call, field_serialize_call, member_name,
+    prepare_ordered_fields, should_skip_field,
+};
 
-use quote::quote;
-use syn::{Data, Fields};
+use quote::{format_ident, quote};
+use syn::{Data, Field, Fields};
+
+/// Member counts from 250 up collide with the reference and null object markers
+const MAX_MEMBER_COUNT: usize = 249;
 
 pub fn generate_version_tolerant_serialize(data: &Data) -> proc_macro2::TokenStream {
     let Data::Struct(data_struct) = data else {
@@ -10,225 +16,192 @@ pub fn generate_version_tolerant_serialize(data: &Data) -> proc_macro2::TokenStr
         };
     };
 
-    match &data_struct.fields {
-        Fields::Named(fields) => {
-            let non_skip: Vec<_> = fields
-                .named
-                .iter()
-                .filter(|f| !should_skip_field(f))
-                .collect();
+    let fields: Vec<_> = data_struct.fields.iter().collect();
+    generate_members_serialize(&fields, |index| match &fields[index].ident {
+        Some(name) => quote! { &self.#name },
+        None => {
+            let index = syn::Index::from(index);
+            quote! { &self.#index }
+        }
+    })
+}
 
-            if non_skip.is_empty() {
-                return quote! { writer.write_u8(0)?; };
-            }
+pub fn generate_version_tolerant_deserialize(data: &Data) -> proc_macro2::TokenStream {
+    let Data::Struct(data_struct) = data else {
+        return quote! {
+            compile_error!("MemoryPackable version_tolerant can only be derived for structs");
+        };
+    };
 
-            let ordered = prepare_ordered_fields(&non_skip);
-            let max_order = ordered.last().map(|f| f.order).unwrap_or(0);
-            let member_count = max_order + 1;
-
-            let serialize_fields: Vec<_> = (0..member_count)
-                .map(|order| {
-                    if let Some(of) = ordered.iter().find(|f| f.order == order) {
-                        let name = of.ident;
-                        quote! {
-                            let start = writer.buffer.len();
-                            memorypack::MemoryPackSerialize::serialize(&self.#name, writer)?;
-                            field_lengths[#order] = writer.buffer.len() - start;
-                        }
-                    } else {
-                        quote! { field_lengths[#order] = 0; }
-                    }
-                })
-                .collect();
-
-            let backpatch_assignments: Vec<_> = (0..member_count)
-                .map(|i| {
-                    quote! { writer.buffer[lengths_pos + #i] = field_lengths[#i] as u8; }
-                })
-                .collect();
+    let fields: Vec<_> = data_struct.fields.iter().collect();
+    let deserialize_members = generate_members_deserialize(&fields);
+    let vars: Vec<_> = (0..fields.len()).map(member_binding).collect();
 
-            quote! {
-                writer.write_u8(#member_count as u8)?;
+    let construct = match &data_struct.fields {
+        Fields::Named(_) => {
+            let names = fields.iter().map(|f| &f.ident);
+            quote! { Self { #(#names: #vars),* } }
+        }
+        Fields::Unnamed(_) => quote! { Self(#(#vars),*) },
+        Fields::Unit => quote! { Self },
+    };
 
-                let lengths_pos = writer.buffer.len();
-                writer.buffer.extend_from_slice(&[0u8; #member_count]);
+    quote! {
+        #deserialize_members
+        Ok(#construct)
+    }
+}
 
-                let mut field_lengths = [0usize; #member_count];
-                #(#serialize_fields)*
+/// Variable holding the field at `index` while a version-tolerant object is decoded
+pub fn member_binding(index: usize) -> syn::Ident {
+    format_ident!("field_{}", index)
+}
 
-                if field_lengths.iter().all(|&len| len < 128) {
-                    #(#backpatch_assignments)*
-                } else {
-                    let data_start = lengths_pos + #member_count;
-                    let data = writer.buffer[data_start..].to_vec();
-                    writer.buffer.truncate(lengths_pos);
-                    for &len in &field_lengths {
-                        memorypack::varint::write_varint(writer, len as i64)?;
-                    }
-                    writer.buffer.extend_from_slice(&data);
-                }
-            }
+/// Non-skipped fields as `(order, index into fields)`, sorted by order
+fn members(fields: &[&Field]) -> syn::Result<Vec<(usize, usize)>> {
+    let non_skip: Vec<_> = fields.iter().copied().filter(|f| !should_skip_field(f)).collect();
+    let ordered = prepare_ordered_fields(&non_skip);
+
+    for pair in ordered.windows(2) {
+        if pair[0].order == pair[1].order {
+            return Err(syn::Error::new_spanned(
+                pair[1].field,
+                format!("Duplicate member order {}", pair[1].order),
+            ));
         }
-        Fields::Unnamed(fields) => {
-            let field_count = fields.unnamed.len();
-            let field_indices: Vec<_> = (0..field_count).map(syn::Index::from).collect();
-
-            let serialize_fields: Vec<_> = field_indices
-                .iter()
-                .enumerate()
-                .map(|(i, idx)| {
-                    quote! {
-                        let start = writer.buffer.len();
-                        memorypack::MemoryPackSerialize::serialize(&self.#idx, writer)?;
-                        field_lengths[#i] = writer.buffer.len() - start;
-                    }
-                })
-                .collect();
-
-            let backpatch_assignments: Vec<_> = (0..field_count)
-                .map(|i| {
-                    quote! { writer.buffer[lengths_pos + #i] = field_lengths[#i] as u8; }
-                })
-                .collect();
+    }
+    if let Some(last) = ordered.last()
+        && last.order >= MAX_MEMBER_COUNT
+    {
+        return Err(syn::Error::new_spanned(
+            last.field,
+            format!("Member order must be below {MAX_MEMBER_COUNT}"),
+        ));
+    }
+
+    Ok(ordered
+        .iter()
+        .map(|of| {
+            let index = fields.iter().position(|f| std::ptr::eq(*f, of.field)).unwrap();
+            (of.order, index)
+        })
+        .collect())
+}
+
+/// Write `fields` as a version-tolerant object; `access` yields a reference to the field at an index
+pub fn generate_members_serialize(
+    fields: &[&Field],
+    access: impl Fn(usize) -> proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    let members = match members(fields) {
+        Ok(members) => members,
+        Err(err) => return err.to_compile_error(),
+    };
+    let Some(&(max_order, _)) = members.last() else {
+        return quote! { writer.write_u8(0)?; };
+    };
 
+    // Orders without a field are written as empty members
+    let serialize_fields: Vec<_> = members
+        .iter()
+        .map(|&(order, index)| {
+            let serialize = field_serialize_call(fields[index], access(index));
             quote! {
-                writer.write_u8(#field_count as u8)?;
+                let start = writer.position();
+                #serialize
+                field_lengths[#order] = writer.position() - start;
+            }
+        })
+        .collect();
 
-                let lengths_pos = writer.buffer.len();
-                writer.buffer.extend_from_slice(&[0u8; #field_count]);
+    generate_member_lengths_serialize(max_order + 1, &serialize_fields)
+}
 
-                let mut field_lengths = [0usize; #field_count];
-                #(#serialize_fields)*
+fn generate_member_lengths_serialize(
+    member_count: usize,
+    serialize_fields: &[proc_macro2::TokenStream],
+) -> proc_macro2::TokenStream {
+    quote! {
+        writer.write_u8(#member_count as u8)?;
 
-                if field_lengths.iter().all(|&len| len < 128) {
-                    #(#backpatch_assignments)*
-                } else {
-                    let data_start = lengths_pos + #field_count;
-                    let data = writer.buffer[data_start..].to_vec();
-                    writer.buffer.truncate(lengths_pos);
-                    for &len in &field_lengths {
-                        memorypack::varint::write_varint(writer, len as i64)?;
-                    }
-                    writer.buffer.extend_from_slice(&data);
-                }
-            }
+        let lengths_reservation = writer.reserve(#member_count)?;
+
+        let mut field_lengths = [0usize; #member_count];
+        #(#serialize_fields)*
+
+        let mut lengths_header = [0u8; #member_count * memorypack::varint::MAX_VARINT_LENGTH];
+        let mut lengths_len = 0;
+        for &len in &field_lengths {
+            lengths_len += memorypack::varint::encode_varint(len as i64, &mut lengths_header[lengths_len..]);
         }
-        Fields::Unit => quote! { writer.write_u8(0)?; },
+        writer.commit(lengths_reservation, &lengths_header[..lengths_len])?;
     }
 }
 
-pub fn generate_version_tolerant_deserialize(data: &Data) -> proc_macro2::TokenStream {
-    let Data::Struct(data_struct) = data else {
-        return quote! {
-            compile_error!("MemoryPackable version_tolerant can only be derived for structs");
-        };
+/// Read a version-tolerant object into one [`member_binding`] per field
+///
+/// Members missing from the payload take the field's default, members at unknown orders are skipped
+pub fn generate_members_deserialize(fields: &[&Field]) -> proc_macro2::TokenStream {
+    let members = match members(fields) {
+        Ok(members) => members,
+        Err(err) => return err.to_compile_error(),
     };
+    let known = members.last().map(|&(order, _)| order + 1).unwrap_or(0);
 
-    match &data_struct.fields {
-        Fields::Named(fields) => {
-            let non_skip: Vec<_> = fields
-                .named
-                .iter()
-                .filter(|f| !should_skip_field(f))
-                .collect();
-
-            if non_skip.is_empty() {
-                return quote! {
-                    let _member_count = reader.read_u8()?;
-                    Ok(Self {})
-                };
+    let read_lengths = if known == 0 {
+        quote! {
+            let mut unknown_length = 0usize;
+            for _ in 0..member_count {
+                unknown_length = unknown_length.saturating_add(memorypack::varint::read_varint_length(reader)?);
             }
-
-            let ordered = prepare_ordered_fields(&non_skip);
-            let all_field_names: Vec<_> = fields.named.iter().map(|f| &f.ident).collect();
-
-            let deserialize_logic: Vec<_> = ordered
-                .iter()
-                .map(|of| {
-                    let name = of.ident;
-                    let order = of.order;
-                    quote! {
-                        let #name = if #order < member_count && lengths[#order] > 0 {
-                            memorypack::MemoryPackDeserialize::deserialize(reader)?
-                        } else {
-                            if #order < member_count {
-                                reader.skip(lengths[#order])?;
-                            }
-                            Default::default()
-                        };
-                    }
-                })
-                .collect();
-
-            let skip_extra_fields = if let Some(max_order) = ordered.last().map(|f| f.order) {
-                let next_order = max_order + 1;
-                quote! {
-                    for i in #next_order..member_count {
-                        reader.skip(lengths[i])?;
-                    }
-                }
-            } else {
-                quote! {}
-            };
-
-            let max_fields = ordered.last().map(|f| f.order + 1).unwrap_or(0);
-
-            if max_fields <= 8 {
-                quote! {
-                    let member_count = reader.read_u8()? as usize;
-                    let mut lengths = [0usize; 8];
-                    for i in 0..member_count.min(8) {
-                        lengths[i] = memorypack::varint::read_varint(reader)? as usize;
-                    }
-                    #(#deserialize_logic)*
-                    #skip_extra_fields
-                    Ok(Self { #(#all_field_names),* })
-                }
-            } else {
-                quote! {
-                    let member_count = reader.read_u8()? as usize;
-                    let mut lengths = Vec::with_capacity(member_count);
-                    for _ in 0..member_count {
-                        lengths.push(memorypack::varint::read_varint(reader)? as usize);
-                    }
-                    #(#deserialize_logic)*
-                    #skip_extra_fields
-                    Ok(Self { #(#all_field_names),* })
+        }
+    } else {
+        quote! {
+            let mut lengths = [0usize; #known];
+            let mut unknown_length = 0usize;
+            for i in 0..member_count {
+                let length = memorypack::varint::read_varint_length(reader)?;
+                if i < #known {
+                    lengths[i] = length;
+                } else {
+                    unknown_length = unknown_length.saturating_add(length);
                 }
             }
         }
-        Fields::Unnamed(fields) => {
-            let field_count = fields.unnamed.len();
-            let field_vars: Vec<_> = (0..field_count)
-                .map(|i| syn::Ident::new(&format!("field_{}", i), proc_macro2::Span::call_site()))
-                .collect();
-
-            let deserialize_fields = field_vars.iter().enumerate().map(|(i, var)| {
-                quote! {
-                    let #var = if #i < member_count {
-                        memorypack::MemoryPackDeserialize::deserialize(reader)?
-                    } else {
-                        Default::default()
-                    };
-                }
-            });
+    };
 
-            quote! {
-                let member_count = reader.read_u8()? as usize;
-                let mut lengths = Vec::with_capacity(member_count);
-                for _ in 0..member_count {
-                    lengths.push(memorypack::varint::read_varint(reader)? as usize);
+    let read_members = (0..known).map(|order| {
+        let Some(&(_, index)) = members.iter().find(|&&(o, _)| o == order) else {
+            return quote! {
+                if #order < member_count {
+                    reader.skip(lengths[#order])?;
                 }
-                #(#deserialize_fields)*
-                for i in #field_count..member_count {
-                    reader.skip(lengths[i])?;
-                }
-                Ok(Self(#(#field_vars),*))
-            }
+            };
+        };
+
+        let var = member_binding(index);
+        let deserialize = field_deserialize_call(fields[index], &member_name(fields[index], index), None);
+        let default = field_default(fields[index]);
+        quote! {
+            let #var = if #order < member_count && lengths[#order] > 0 {
+                #deserialize
+            } else {
+                #default
+            };
         }
-        Fields::Unit => quote! {
-            let _member_count = reader.read_u8()?;
-            Ok(Self)
-        },
+    });
+
+    let skipped = fields.iter().enumerate().filter(|(_, f)| should_skip_field(f)).map(|(index, f)| {
+        let var = member_binding(index);
+        let default = field_default(f);
+        quote! { let #var = #default; }
+    });
+
+    quote! {
+        let member_count = reader.read_u8()? as usize;
+        #read_lengths
+        #(#read_members)*
+        reader.skip(unknown_length)?;
+        #(#skipped)*
     }
 }
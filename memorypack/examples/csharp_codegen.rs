@@ -0,0 +1,64 @@
+//! Print the C# counterparts of a few Rust types, or write them to the path given as the first argument
+//!
+//! ```sh
+//! cargo run --example csharp_codegen -- Protocol.g.cs
+//! ```
+
+use memorypack::CSharpGenerator;
+use memorypack::prelude::*;
+
+#[derive(MemoryPackable, Debug, Default, Clone, Copy, PartialEq)]
+#[repr(i32)]
+enum Rarity {
+    #[default]
+    Common = 0,
+    Rare = 1,
+    Legendary = 2,
+}
+
+#[derive(MemoryPackable, Debug, Default)]
+struct Item {
+    id: i32,
+    name: String,
+    rarity: Option<i32>,
+}
+
+#[derive(MemoryPackable, Debug)]
+#[memorypack(version_tolerant)]
+struct Player {
+    #[memorypack(order = 0)]
+    name: String,
+    #[memorypack(order = 1)]
+    level: u16,
+    #[memorypack(order = 3)]
+    inventory: Vec<Item>,
+    #[memorypack(order = 4)]
+    favorite: Rarity,
+}
+
+#[derive(MemoryPackable, Debug)]
+enum Message {
+    #[memorypack(tag = 0)]
+    Join(Player),
+    #[memorypack(tag = 1)]
+    Chat {
+        from: String,
+        text: String,
+        #[memorypack(nullable_object)]
+        attachment: Option<Item>,
+    },
+}
+
+fn main() -> Result<(), memorypack::csharp::CSharpError> {
+    let generator = CSharpGenerator::new()
+        .with_namespace("Game.Protocol")
+        .with_type::<Message>();
+
+    match std::env::args().nth(1) {
+        Some(path) => generator.write_to(path),
+        None => {
+            print!("{}", generator.generate()?);
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,143 @@
+//! Length-delimited framing for MemoryPack payloads over tokio streams.
+//!
+//! Every frame is a little-endian `i32` payload length followed by the payload itself,
+//! the same layout written by [`MemoryPackSerializer::serialize_to_async`] and read by
+//! [`MemoryPackSerializer::deserialize_from_async`].
+//!
+//! [`MemoryPackSerializer::serialize_to_async`]: crate::MemoryPackSerializer::serialize_to_async
+//! [`MemoryPackSerializer::deserialize_from_async`]: crate::MemoryPackSerializer::deserialize_from_async
+
+use crate::error::MemoryPackError;
+use crate::options::{MemoryPackReaderLimits, MemoryPackSerializerOptions};
+use crate::traits::{MemoryPackDeserialize, MemoryPackSerialize};
+use crate::reader::MemoryPackReader;
+use crate::writer::MemoryPackWriter;
+
+use bytes::{Buf, BufMut, BytesMut};
+use std::marker::PhantomData;
+use tokio_util::codec::{Decoder, Encoder};
+
+pub(crate) const FRAME_HEADER_LENGTH: usize = 4;
+pub const DEFAULT_MAX_FRAME_LENGTH: usize = 8 * 1024 * 1024;
+
+/// `tokio_util` codec encoding and decoding `T` as length-prefixed MemoryPack frames
+pub struct MemoryPackCodec<T> {
+    max_frame_length: usize,
+    options: MemoryPackSerializerOptions,
+    limits: MemoryPackReaderLimits,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T> MemoryPackCodec<T> {
+    pub fn new() -> Self {
+        Self::with_max_frame_length(DEFAULT_MAX_FRAME_LENGTH)
+    }
+
+    pub fn with_max_frame_length(max_frame_length: usize) -> Self {
+        Self {
+            max_frame_length,
+            options: MemoryPackSerializerOptions::default(),
+            limits: MemoryPackReaderLimits::default(),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Encode frames using `options`
+    pub fn with_options(mut self, options: MemoryPackSerializerOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Decode frames under `limits`
+    pub fn with_limits(mut self, limits: MemoryPackReaderLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    #[inline]
+    pub fn max_frame_length(&self) -> usize {
+        self.max_frame_length
+    }
+}
+
+impl<T> Default for MemoryPackCodec<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Clone for MemoryPackCodec<T> {
+    fn clone(&self) -> Self {
+        Self::with_max_frame_length(self.max_frame_length).with_options(self.options)
+            .with_limits(self.limits)
+    }
+}
+
+impl<T> std::fmt::Debug for MemoryPackCodec<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MemoryPackCodec")
+            .field("max_frame_length", &self.max_frame_length)
+            .field("options", &self.options)
+            .field("limits", &self.limits)
+            .finish()
+    }
+}
+
+impl<T: MemoryPackSerialize> Encoder<T> for MemoryPackCodec<T> {
+    type Error = MemoryPackError;
+
+    fn encode(&mut self, item: T, dst: &mut BytesMut) -> Result<(), Self::Error> {
+        let start = dst.len();
+        dst.put_i32_le(0);
+
+        let mut sink = BufMut::writer(&mut *dst);
+        let mut writer = MemoryPackWriter::from_stream(&mut sink).with_options(self.options);
+        if let Err(error) = item.serialize(&mut writer).and_then(|_| writer.flush()) {
+            dst.truncate(start);
+            return Err(error);
+        }
+
+        let length = dst.len() - start - FRAME_HEADER_LENGTH;
+        if length > self.max_frame_length {
+            dst.truncate(start);
+            return Err(MemoryPackError::FrameTooLarge(length, self.max_frame_length));
+        }
+
+        dst[start..start + FRAME_HEADER_LENGTH].copy_from_slice(&(length as i32).to_le_bytes());
+        Ok(())
+    }
+}
+
+impl<T: MemoryPackDeserialize> Decoder for MemoryPackCodec<T> {
+    type Item = T;
+    type Error = MemoryPackError;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        if src.len() < FRAME_HEADER_LENGTH {
+            return Ok(None);
+        }
+
+        let length = i32::from_le_bytes([src[0], src[1], src[2], src[3]]);
+        if length < 0 {
+            return Err(MemoryPackError::InvalidLength(length));
+        }
+
+        let length = length as usize;
+        if length > self.max_frame_length {
+            return Err(MemoryPackError::FrameTooLarge(length, self.max_frame_length));
+        }
+
+        let frame_length = FRAME_HEADER_LENGTH + length;
+        if src.len() < frame_length {
+            src.reserve(frame_length - src.len());
+            return Ok(None);
+        }
+
+        src.advance(FRAME_HEADER_LENGTH);
+        let payload = src.split_to(length);
+        let mut reader = MemoryPackReader::new(&payload).with_limits(self.limits);
+        T::deserialize(&mut reader)
+            .map(Some)
+            .map_err(|error| error.at(reader.position()))
+    }
+}
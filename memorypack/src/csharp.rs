@@ -0,0 +1,445 @@
+//! C# `[MemoryPackable]` definitions generated from [schemas](crate::schema), so Rust and C# types
+//! share one source of truth
+//!
+//! ```no_run
+//! # use memorypack::prelude::*;
+//! # #[derive(MemoryPackable)]
+//! # struct Player { name: String }
+//! // build.rs or a small binary
+//! memorypack::CSharpGenerator::new()
+//!     .with_namespace("Game.Protocol")
+//!     .with_type::<Player>()
+//!     .write_to("../Unity/Assets/Scripts/Protocol.g.cs")
+//!     .unwrap();
+//! ```
+
+use crate::schema::{
+    EnumSchema, KnownType, MemberSchema, ObjectLayout, ObjectSchema, Primitive, Schema, UnionSchema,
+};
+use crate::traits::MemoryPackSchema;
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::path::Path;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum CSharpError {
+    #[error("{0} cannot be expressed in C#: {1}")]
+    Unsupported(String, &'static str),
+
+    #[error("Two different types are both named {0}")]
+    ConflictingType(String),
+
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+}
+
+/// Generates C# types matching the wire layout of Rust types, including every type they reference
+#[derive(Debug, Clone, Default)]
+pub struct CSharpGenerator {
+    namespace: Option<String>,
+    roots: Vec<Schema>,
+}
+
+impl CSharpGenerator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Namespace wrapping the generated types
+    pub fn with_namespace(mut self, namespace: impl Into<String>) -> Self {
+        self.namespace = Some(namespace.into());
+        self
+    }
+
+    /// Generate `T` and the types it references
+    pub fn with_type<T: MemoryPackSchema>(mut self) -> Self {
+        self.roots.push(T::schema());
+        self
+    }
+
+    /// C# source declaring every collected type
+    pub fn generate(&self) -> Result<String, CSharpError> {
+        let mut types = TypeSet::default();
+        for root in &self.roots {
+            types.collect(root, "root type")?;
+        }
+
+        let mut body = String::new();
+        for (i, name) in types.order.iter().enumerate() {
+            if i > 0 {
+                body.push('\n');
+            }
+            types.declare(name, &mut body)?;
+        }
+
+        let mut source = String::from(HEADER);
+        match &self.namespace {
+            Some(namespace) => {
+                writeln!(source, "namespace {namespace}\n{{").unwrap();
+                for line in body.lines() {
+                    if line.is_empty() {
+                        source.push('\n');
+                    } else {
+                        writeln!(source, "    {line}").unwrap();
+                    }
+                }
+                source.push_str("}\n");
+            }
+            None => source.push_str(&body),
+        }
+        Ok(source)
+    }
+
+    /// Write the generated source to `path`, leaving the file untouched when it is already up to
+    /// date so build scripts do not trigger needless Unity reimports
+    pub fn write_to(&self, path: impl AsRef<Path>) -> Result<(), CSharpError> {
+        let path = path.as_ref();
+        let source = self.generate()?;
+        if std::fs::read_to_string(path).is_ok_and(|existing| existing == source) {
+            return Ok(());
+        }
+        if let Some(parent) = path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, source)?;
+        Ok(())
+    }
+}
+
+const HEADER: &str = "\
+// <auto-generated>
+// Generated from Rust types by memorypack; changes will be overwritten
+// </auto-generated>
+#nullable enable
+using System;
+using System.Collections.Generic;
+using System.Numerics;
+using System.Runtime.InteropServices;
+using MemoryPack;
+
+";
+
+enum Declaration {
+    Object(ObjectSchema),
+    Union(UnionSchema),
+    Enum(EnumSchema),
+}
+
+/// Named types in the order they were first reached
+#[derive(Default)]
+struct TypeSet {
+    order: Vec<String>,
+    declarations: HashMap<String, Declaration>,
+    /// Union interfaces each class implements
+    interfaces: HashMap<String, Vec<String>>,
+}
+
+impl TypeSet {
+    /// Register `declaration` under `name`; false if it was already registered
+    fn insert(&mut self, name: String, declaration: Declaration) -> Result<bool, CSharpError> {
+        if let Some(existing) = self.declarations.get(&name) {
+            let same = match (existing, &declaration) {
+                (Declaration::Object(a), Declaration::Object(b)) => a == b,
+                (Declaration::Union(a), Declaration::Union(b)) => a == b,
+                (Declaration::Enum(a), Declaration::Enum(b)) => a == b,
+                _ => false,
+            };
+            return if same { Ok(false) } else { Err(CSharpError::ConflictingType(name)) };
+        }
+        self.order.push(name.clone());
+        self.declarations.insert(name, declaration);
+        Ok(true)
+    }
+
+    /// Register the named types reachable from `schema`; `context` names it in errors
+    fn collect(&mut self, schema: &Schema, context: &str) -> Result<(), CSharpError> {
+        match schema {
+            Schema::Collection(inner)
+            | Schema::Array(inner, _)
+            | Schema::Nullable(inner)
+            | Schema::NullableReference(inner) => self.collect(inner, context),
+            Schema::Map(key, value) => {
+                self.collect(key, context)?;
+                self.collect(value, context)
+            }
+            Schema::Tuple(items) => items.iter().try_for_each(|item| self.collect(item, context)),
+            Schema::Object(object) => self.collect_object(object.name.to_string(), object),
+            Schema::Union(union) => self.collect_union(union),
+            Schema::Enum(schema) => {
+                self.insert(schema.name.to_string(), Declaration::Enum(schema.clone()))?;
+                Ok(())
+            }
+            Schema::Unit => Err(CSharpError::Unsupported(context.into(), "unit structs write no bytes")),
+            Schema::MultiDimArray(_) => Err(CSharpError::Unsupported(
+                context.into(),
+                "MultiDimArray has no fixed rank; declare the C# member by hand",
+            )),
+            Schema::Custom(formatter) => Err(CSharpError::Unsupported(
+                format!("{context}, written by {formatter}"),
+                "custom formatters, schema overrides and types without a schema need a hand-written C# counterpart",
+            )),
+            Schema::Primitive(_) | Schema::String | Schema::Known(_) | Schema::Recursive(_) => Ok(()),
+        }
+    }
+
+    fn collect_object(&mut self, name: String, object: &ObjectSchema) -> Result<(), CSharpError> {
+        if !self.insert(name.clone(), Declaration::Object(object.clone()))? {
+            return Ok(());
+        }
+        for member in &object.members {
+            self.collect(&member.schema, &format!("{name}.{}", member.name))?;
+        }
+        Ok(())
+    }
+
+    fn collect_union(&mut self, union: &UnionSchema) -> Result<(), CSharpError> {
+        if !self.insert(union.name.to_string(), Declaration::Union(union.clone()))? {
+            return Ok(());
+        }
+        for variant in &union.variants {
+            let context = format!("{}::{}", union.name, variant.name);
+            let class = self.variant_class(&variant.schema, &context)?;
+            self.interfaces.entry(class).or_default().push(interface_name(union.name));
+        }
+        Ok(())
+    }
+
+    /// Register the class a union variant is written as and return its name
+    fn variant_class(&mut self, schema: &Schema, context: &str) -> Result<String, CSharpError> {
+        match schema {
+            Schema::Object(object) if object.layout != ObjectLayout::Unmanaged => {
+                self.collect_object(object.name.to_string(), object)?;
+                Ok(object.name.to_string())
+            }
+            Schema::Recursive(name)
+                if matches!(self.declarations.get(*name), Some(Declaration::Object(_))) =>
+            {
+                Ok(name.to_string())
+            }
+            _ => Err(CSharpError::Unsupported(
+                context.into(),
+                "C# union members must be MemoryPackable classes",
+            )),
+        }
+    }
+
+    fn declare(&self, name: &str, out: &mut String) -> Result<(), CSharpError> {
+        match &self.declarations[name] {
+            Declaration::Object(object) if object.layout == ObjectLayout::Unmanaged => {
+                self.declare_struct(name, object, out)
+            }
+            Declaration::Object(object) => self.declare_class(name, object, out),
+            Declaration::Union(union) => {
+                self.declare_union(union, out);
+                Ok(())
+            }
+            Declaration::Enum(schema) => {
+                declare_enum(schema, out);
+                Ok(())
+            }
+        }
+    }
+
+    fn declare_class(&self, name: &str, object: &ObjectSchema, out: &mut String) -> Result<(), CSharpError> {
+        // Standard objects only accept `MemoryPackOrder` under the explicit layout
+        let generate_type = match object.layout {
+            ObjectLayout::VersionTolerant => "(GenerateType.VersionTolerant)",
+            ObjectLayout::Circular => "(GenerateType.CircularReference)",
+            ObjectLayout::Standard if !object.members.is_empty() => "(SerializeLayout.Explicit)",
+            ObjectLayout::Standard | ObjectLayout::Unmanaged => "",
+        };
+        let bases = self
+            .interfaces
+            .get(name)
+            .map(|interfaces| format!(" : {}", interfaces.join(", ")))
+            .unwrap_or_default();
+
+        writeln!(out, "[MemoryPackable{generate_type}]").unwrap();
+        writeln!(out, "public partial class {name}{bases}\n{{").unwrap();
+        for (i, member) in object.members.iter().enumerate() {
+            if i > 0 {
+                out.push('\n');
+            }
+            let (ty, initializer) = self.member_type(name, member)?;
+            writeln!(out, "    [MemoryPackOrder({})]", member.order).unwrap();
+            writeln!(out, "    public {ty} {} {{ get; set; }}{initializer}", property_name(member.name)).unwrap();
+        }
+        out.push_str("}\n");
+        Ok(())
+    }
+
+    /// Unmanaged types are copied as raw bytes in C# too, so they become plain sequential structs
+    fn declare_struct(&self, name: &str, object: &ObjectSchema, out: &mut String) -> Result<(), CSharpError> {
+        writeln!(out, "[StructLayout(LayoutKind.Sequential)]").unwrap();
+        writeln!(out, "public struct {name}\n{{").unwrap();
+        for member in &object.members {
+            let (ty, _) = self.member_type(name, member)?;
+            writeln!(out, "    public {ty} {};", property_name(member.name)).unwrap();
+        }
+        out.push_str("}\n");
+        Ok(())
+    }
+
+    fn declare_union(&self, union: &UnionSchema, out: &mut String) {
+        writeln!(out, "[MemoryPackable]").unwrap();
+        for variant in &union.variants {
+            let class = match &variant.schema {
+                Schema::Object(object) => object.name.to_string(),
+                Schema::Recursive(name) => name.to_string(),
+                _ => unreachable!("checked while collecting"),
+            };
+            writeln!(out, "[MemoryPackUnion({}, typeof({class}))]", variant.tag).unwrap();
+        }
+        writeln!(out, "public partial interface {}\n{{\n}}", interface_name(union.name)).unwrap();
+    }
+
+    /// C# type of a member and the initializer keeping non-nullable references non-null
+    fn member_type(&self, owner: &str, member: &MemberSchema) -> Result<(String, &'static str), CSharpError> {
+        let context = || format!("{owner}.{}", member.name);
+        let ty = self.type_name(&member.schema, &context)?;
+        let initializer = match &member.schema {
+            Schema::String => " = \"\";",
+            Schema::Collection(_) | Schema::Map(..) => " = new();",
+            schema if !self.is_value_type(schema) && !is_nullable(schema) => " = null!;",
+            _ => "",
+        };
+        Ok((ty, initializer))
+    }
+
+    fn type_name(&self, schema: &Schema, context: &dyn Fn() -> String) -> Result<String, CSharpError> {
+        Ok(match schema {
+            Schema::Primitive(primitive) => primitive_name(*primitive).into(),
+            Schema::String => "string".into(),
+            Schema::Collection(inner) => format!("List<{}>", self.type_name(inner, context)?),
+            Schema::Array(inner, _) => format!("{}[]", self.type_name(inner, context)?),
+            Schema::Map(key, value) => format!(
+                "Dictionary<{}, {}>",
+                self.type_name(key, context)?,
+                self.type_name(value, context)?
+            ),
+            Schema::Tuple(items) => {
+                let items = items
+                    .iter()
+                    .map(|item| self.type_name(item, context))
+                    .collect::<Result<Vec<_>, _>>()?;
+                match items.as_slice() {
+                    [single] => format!("ValueTuple<{single}>"),
+                    items => format!("({})", items.join(", ")),
+                }
+            }
+            Schema::Nullable(inner) if !self.is_value_type(inner) => {
+                return Err(CSharpError::Unsupported(
+                    context(),
+                    "C# reads a has-value flag only before value types; use NullableString, NullableVec or nullable_object",
+                ));
+            }
+            Schema::Nullable(inner) | Schema::NullableReference(inner) => {
+                format!("{}?", self.type_name(inner, context)?)
+            }
+            Schema::Known(known) => known_name(*known).into(),
+            Schema::Object(object) => object.name.into(),
+            Schema::Enum(schema) => schema.name.into(),
+            Schema::Union(union) => interface_name(union.name),
+            Schema::Recursive(name) => match self.declarations.get(*name) {
+                Some(Declaration::Union(_)) => interface_name(name),
+                _ => name.to_string(),
+            },
+            Schema::Unit | Schema::MultiDimArray(_) | Schema::Custom(_) => {
+                unreachable!("rejected while collecting")
+            }
+        })
+    }
+
+    fn is_value_type(&self, schema: &Schema) -> bool {
+        match schema {
+            Schema::Primitive(_) | Schema::Tuple(_) | Schema::Nullable(_) | Schema::Enum(_) => true,
+            Schema::Known(known) => !matches!(known, KnownType::Uri),
+            Schema::Object(object) => object.layout == ObjectLayout::Unmanaged,
+            Schema::Recursive(name) => matches!(
+                self.declarations.get(*name),
+                Some(Declaration::Object(object)) if object.layout == ObjectLayout::Unmanaged
+            ),
+            _ => false,
+        }
+    }
+}
+
+fn declare_enum(schema: &EnumSchema, out: &mut String) {
+    if schema.flags {
+        out.push_str("[Flags]\n");
+    }
+    writeln!(out, "public enum {} : {}\n{{", schema.name, primitive_name(schema.underlying)).unwrap();
+    for variant in &schema.variants {
+        writeln!(out, "    {} = {},", variant.name, variant.value).unwrap();
+    }
+    out.push_str("}\n");
+}
+
+#[inline]
+fn is_nullable(schema: &Schema) -> bool {
+    matches!(schema, Schema::Nullable(_) | Schema::NullableReference(_))
+}
+
+fn interface_name(union: &str) -> String {
+    format!("I{union}")
+}
+
+/// `snake_case` field names as PascalCase properties; tuple struct positions become `Item0`, `Item1`, ..
+fn property_name(member: &str) -> String {
+    if member.starts_with(|c: char| c.is_ascii_digit()) {
+        return format!("Item{member}");
+    }
+    member
+        .split('_')
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            let mut chars = word.chars();
+            chars
+                .next()
+                .map(|first| first.to_uppercase().chain(chars).collect::<String>())
+                .unwrap_or_default()
+        })
+        .collect()
+}
+
+fn primitive_name(primitive: Primitive) -> &'static str {
+    match primitive {
+        Primitive::Bool => "bool",
+        Primitive::I8 => "sbyte",
+        Primitive::U8 => "byte",
+        Primitive::I16 => "short",
+        Primitive::U16 => "ushort",
+        Primitive::I32 => "int",
+        Primitive::U32 => "uint",
+        Primitive::I64 => "long",
+        Primitive::U64 => "ulong",
+        Primitive::I128 => "Int128",
+        Primitive::U128 => "UInt128",
+        Primitive::F32 => "float",
+        Primitive::F64 => "double",
+        Primitive::Char => "char",
+    }
+}
+
+fn known_name(known: KnownType) -> &'static str {
+    match known {
+        KnownType::Guid => "Guid",
+        KnownType::Decimal => "decimal",
+        KnownType::Half => "Half",
+        KnownType::BigInteger => "BigInteger",
+        KnownType::Uri => "Uri",
+        KnownType::TimeSpan => "TimeSpan",
+        KnownType::DateTime => "DateTime",
+        KnownType::DateTimeOffset => "DateTimeOffset",
+        KnownType::TimeOnly => "TimeOnly",
+        KnownType::DateOnly => "DateOnly",
+        KnownType::Vector2 => "Vector2",
+        KnownType::Vector3 => "Vector3",
+        KnownType::Vector4 => "Vector4",
+        KnownType::Quaternion => "Quaternion",
+        KnownType::Matrix3x2 => "Matrix3x2",
+        KnownType::Matrix4x4 => "Matrix4x4",
+        KnownType::Complex => "Complex",
+    }
+}
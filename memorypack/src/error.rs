@@ -1,3 +1,6 @@
+use crate::options::ReaderLimit;
+
+use std::fmt;
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -20,6 +23,12 @@ pub enum MemoryPackError {
     #[error("Deserialization error: {0}")]
     DeserializationError(String),
 
+    #[error("Expected an array of length {0}, found {1}")]
+    ArrayLengthMismatch(usize, i32),
+
+    #[error("Frame length {0} exceeds the maximum of {1}")]
+    FrameTooLarge(usize, usize),
+
     #[error("Buffer too small")]
     BufferTooSmall,
 
@@ -34,4 +43,159 @@ pub enum MemoryPackError {
 
     #[error("UTF-16 strings are not supported for zero-copy deserialization")]
     Utf16NotSupportedForZeroCopy,
+
+    #[error("Streaming readers are not supported for zero-copy deserialization")]
+    StreamNotSupportedForZeroCopy,
+
+    #[error("Data is not aligned for zero-copy deserialization of a {0} slice")]
+    MisalignedForZeroCopy(&'static str),
+
+    #[error("Reader limit exceeded: {0} above the maximum of {1}")]
+    LimitExceeded(ReaderLimit, usize),
+
+    #[error("Invalid discriminant {1} for enum {0}")]
+    InvalidDiscriminant(&'static str, i32),
+
+    #[error("Unknown union tag {1} for {0}")]
+    UnknownUnionTag(&'static str, u16),
+
+    #[error("Length {0} is out of range")]
+    LengthOutOfRange(i64),
+
+    #[error("{0} expects {1} members, found {2}")]
+    MemberCountMismatch(&'static str, usize, usize),
+
+    /// An error with the position and path it was raised at; match on [`kind`](Self::kind)
+    /// to handle the underlying error
+    #[error("{0} ({1})")]
+    Located(Box<MemoryPackError>, Box<ErrorLocation>),
+}
+
+impl MemoryPackError {
+    /// The error itself, without the location it was raised at
+    #[inline]
+    pub fn kind(&self) -> &MemoryPackError {
+        match self {
+            Self::Located(error, _) => error,
+            error => error,
+        }
+    }
+
+    #[inline]
+    pub fn location(&self) -> Option<&ErrorLocation> {
+        match self {
+            Self::Located(_, location) => Some(location),
+            _ => None,
+        }
+    }
+
+    /// Record the reader position the error was raised at, keeping an earlier one
+    pub fn at(self, position: u64) -> Self {
+        match self {
+            Self::Located(..) => self,
+            error => Self::Located(
+                Box::new(error),
+                Box::new(ErrorLocation {
+                    position,
+                    type_name: None,
+                    path: Vec::new(),
+                }),
+            ),
+        }
+    }
+
+    /// Prefix the error path with a struct field or member
+    #[inline]
+    pub fn in_field(self, name: &'static str, position: u64) -> Self {
+        self.with_segment(PathSegment::Field(name), position)
+    }
+
+    /// Prefix the error path with a collection element
+    #[inline]
+    pub fn in_element(self, index: usize, position: u64) -> Self {
+        self.with_segment(PathSegment::Index(index), position)
+    }
+
+    /// Prefix the error path with a union variant
+    #[inline]
+    pub fn in_variant(self, name: &'static str, position: u64) -> Self {
+        self.with_segment(PathSegment::Variant(name), position)
+    }
+
+    /// Name the type the error path starts from; outer types replace inner ones
+    pub fn in_type(self, type_name: &'static str, position: u64) -> Self {
+        let mut error = self.at(position);
+        if let Self::Located(_, location) = &mut error {
+            location.type_name = Some(type_name);
+        }
+        error
+    }
+
+    fn with_segment(self, segment: PathSegment, position: u64) -> Self {
+        let mut error = self.at(position);
+        if let Self::Located(_, location) = &mut error {
+            location.type_name = None;
+            location.path.push(segment);
+        }
+        error
+    }
+}
+
+/// One step of an [`ErrorLocation`] path
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PathSegment {
+    Field(&'static str),
+    Index(usize),
+    Variant(&'static str),
+}
+
+/// Where in the payload a deserialization error was raised
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ErrorLocation {
+    position: u64,
+    type_name: Option<&'static str>,
+    // Innermost segment first, as segments are added while the error propagates outwards
+    path: Vec<PathSegment>,
+}
+
+impl ErrorLocation {
+    /// Reader position when the error was raised
+    #[inline]
+    pub fn position(&self) -> u64 {
+        self.position
+    }
+
+    /// The outermost derived type on the path, if any
+    #[inline]
+    pub fn type_name(&self) -> Option<&'static str> {
+        self.type_name
+    }
+
+    /// Path segments from the outermost value inwards
+    pub fn path(&self) -> impl Iterator<Item = &PathSegment> {
+        self.path.iter().rev()
+    }
+}
+
+impl fmt::Display for ErrorLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "at byte {}", self.position)?;
+        if self.type_name.is_none() && self.path.is_empty() {
+            return Ok(());
+        }
+
+        f.write_str(" in ")?;
+        if let Some(type_name) = self.type_name {
+            f.write_str(type_name)?;
+        }
+        for (i, segment) in self.path().enumerate() {
+            match segment {
+                PathSegment::Field(name) if i == 0 && self.type_name.is_none() => f.write_str(name)?,
+                PathSegment::Field(name) => write!(f, ".{}", name)?,
+                PathSegment::Index(index) => write!(f, "[{}]", index)?,
+                PathSegment::Variant(name) => write!(f, "::{}", name)?,
+            }
+        }
+        Ok(())
+    }
 }
@@ -0,0 +1,196 @@
+//! Formatters for types that cannot implement the serialization traits themselves
+//!
+//! A formatter is a marker type implementing [`MemoryPackFormatter<T>`], the counterpart of
+//! C# `IMemoryPackFormatter<T>`. Orphan rules keep foreign types from implementing
+//! [`MemoryPackSerialize`] here, but a local formatter can implement the trait for any `T`.
+//! Derived fields pick one with `#[memorypack(formatter = "path::ToFormatter")]`, and the adapters
+//! below lift element formatters to containers.
+
+use crate::error::MemoryPackError;
+use crate::reader::MemoryPackReader;
+use crate::traits::{MemoryPackDeserialize, MemoryPackSerialize, NULL_OBJECT};
+use crate::writer::MemoryPackWriter;
+use std::collections::HashMap;
+use std::hash::{BuildHasher, Hash};
+use std::marker::PhantomData;
+
+pub trait MemoryPackFormatter<T> {
+    fn serialize(value: &T, writer: &mut MemoryPackWriter) -> Result<(), MemoryPackError>;
+
+    fn deserialize(reader: &mut MemoryPackReader) -> Result<T, MemoryPackError>;
+}
+
+/// Uses the type's own [`MemoryPackSerialize`] and [`MemoryPackDeserialize`] impls
+pub struct DefaultFormatter;
+
+impl<T: MemoryPackSerialize + MemoryPackDeserialize> MemoryPackFormatter<T> for DefaultFormatter {
+    #[inline(always)]
+    fn serialize(value: &T, writer: &mut MemoryPackWriter) -> Result<(), MemoryPackError> {
+        value.serialize(writer)
+    }
+
+    #[inline(always)]
+    fn deserialize(reader: &mut MemoryPackReader) -> Result<T, MemoryPackError> {
+        T::deserialize(reader)
+    }
+}
+
+#[inline(always)]
+fn read_collection_length(reader: &mut MemoryPackReader) -> Result<usize, MemoryPackError> {
+    Ok(reader.read_collection_length()?.unwrap_or(0))
+}
+
+/// `Vec<T>` with each element written by `F`
+pub struct VecFormatter<F>(PhantomData<F>);
+
+impl<T, F: MemoryPackFormatter<T>> MemoryPackFormatter<Vec<T>> for VecFormatter<F> {
+    fn serialize(value: &Vec<T>, writer: &mut MemoryPackWriter) -> Result<(), MemoryPackError> {
+        writer.write_i32(value.len() as i32)?;
+        for item in value {
+            F::serialize(item, writer)?;
+        }
+        Ok(())
+    }
+
+    fn deserialize(reader: &mut MemoryPackReader) -> Result<Vec<T>, MemoryPackError> {
+        let len = read_collection_length(reader)?;
+        let mut items = Vec::with_capacity(reader.reserve_elements::<T>(len)?);
+        for i in 0..len {
+            items.push(reader.element(i, F::deserialize)?);
+        }
+        Ok(items)
+    }
+}
+
+/// `Option<T>` in the `Nullable<T>` layout of `Option`, with the value written by `F`
+pub struct OptionFormatter<F>(PhantomData<F>);
+
+impl<T: Default, F: MemoryPackFormatter<T>> MemoryPackFormatter<Option<T>> for OptionFormatter<F> {
+    fn serialize(value: &Option<T>, writer: &mut MemoryPackWriter) -> Result<(), MemoryPackError> {
+        match value {
+            Some(value) => {
+                writer.write_i32(1)?;
+                F::serialize(value, writer)
+            }
+            None => {
+                writer.write_i32(0)?;
+                F::serialize(&T::default(), writer)
+            }
+        }
+    }
+
+    fn deserialize(reader: &mut MemoryPackReader) -> Result<Option<T>, MemoryPackError> {
+        let has_value = reader.read_i32()?;
+        let value = F::deserialize(reader)?;
+        Ok((has_value != 0).then_some(value))
+    }
+}
+
+/// `Option<T>` like a nullable C# class, see [`NullableObject`](crate::NullableObject); unlike
+/// [`OptionFormatter`] it does not need `T: Default`
+pub struct NullableObjectFormatter<F>(PhantomData<F>);
+
+impl<T, F: MemoryPackFormatter<T>> MemoryPackFormatter<Option<T>> for NullableObjectFormatter<F> {
+    fn serialize(value: &Option<T>, writer: &mut MemoryPackWriter) -> Result<(), MemoryPackError> {
+        match value {
+            Some(value) => F::serialize(value, writer),
+            None => writer.write_u8(NULL_OBJECT),
+        }
+    }
+
+    fn deserialize(reader: &mut MemoryPackReader) -> Result<Option<T>, MemoryPackError> {
+        if reader.peek_u8()? == NULL_OBJECT {
+            reader.read_u8()?;
+            return Ok(None);
+        }
+        F::deserialize(reader).map(Some)
+    }
+}
+
+/// `HashMap<K, V>` with keys written by `KF` and values by `VF`
+pub struct HashMapFormatter<KF, VF>(PhantomData<(KF, VF)>);
+
+impl<K, V, S, KF, VF> MemoryPackFormatter<HashMap<K, V, S>> for HashMapFormatter<KF, VF>
+where
+    K: Eq + Hash,
+    S: BuildHasher + Default,
+    KF: MemoryPackFormatter<K>,
+    VF: MemoryPackFormatter<V>,
+{
+    fn serialize(value: &HashMap<K, V, S>, writer: &mut MemoryPackWriter) -> Result<(), MemoryPackError> {
+        writer.write_i32(value.len() as i32)?;
+        for (key, value) in value {
+            KF::serialize(key, writer)?;
+            VF::serialize(value, writer)?;
+        }
+        Ok(())
+    }
+
+    fn deserialize(reader: &mut MemoryPackReader) -> Result<HashMap<K, V, S>, MemoryPackError> {
+        let len = read_collection_length(reader)?;
+        let mut map = HashMap::with_capacity_and_hasher(reader.reserve_elements::<(K, V)>(len)?, S::default());
+        for i in 0..len {
+            let (key, value) =
+                reader.element(i, |reader| Ok((KF::deserialize(reader)?, VF::deserialize(reader)?)))?;
+            map.insert(key, value);
+        }
+        Ok(map)
+    }
+}
+
+/// `T` serialized through the formatter `F`, for places that need a [`MemoryPackSerialize`] type
+/// such as the element type of another container
+pub struct Formatted<T, F> {
+    pub value: T,
+    formatter: PhantomData<F>,
+}
+
+impl<T, F> Formatted<T, F> {
+    #[inline]
+    pub fn new(value: T) -> Self {
+        Self { value, formatter: PhantomData }
+    }
+
+    #[inline]
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<T: Clone, F> Clone for Formatted<T, F> {
+    fn clone(&self) -> Self {
+        Self::new(self.value.clone())
+    }
+}
+
+impl<T: std::fmt::Debug, F> std::fmt::Debug for Formatted<T, F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.value.fmt(f)
+    }
+}
+
+impl<T: PartialEq, F> PartialEq for Formatted<T, F> {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+impl<T: Default, F> Default for Formatted<T, F> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+impl<T, F: MemoryPackFormatter<T>> MemoryPackSerialize for Formatted<T, F> {
+    #[inline]
+    fn serialize(&self, writer: &mut MemoryPackWriter) -> Result<(), MemoryPackError> {
+        F::serialize(&self.value, writer)
+    }
+}
+
+impl<T, F: MemoryPackFormatter<T>> MemoryPackDeserialize for Formatted<T, F> {
+    #[inline]
+    fn deserialize(reader: &mut MemoryPackReader) -> Result<Self, MemoryPackError> {
+        F::deserialize(reader).map(Self::new)
+    }
+}
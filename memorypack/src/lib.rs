@@ -4,23 +4,40 @@
 mod reader;
 mod writer;
 
+#[cfg(feature = "tokio")]
+pub mod codec;
+pub mod csharp;
 pub mod error;
+pub mod formatter;
+pub mod options;
+pub mod schema;
+#[cfg(feature = "serde")]
+pub mod serde;
 pub mod serializer;
 pub mod state;
 pub mod traits;
 pub mod varint;
-
-pub use error::MemoryPackError;
+pub mod with;
+
+#[cfg(feature = "tokio")]
+pub use codec::MemoryPackCodec;
+pub use csharp::CSharpGenerator;
+pub use error::{ErrorLocation, MemoryPackError, PathSegment};
+pub use formatter::MemoryPackFormatter;
+pub use options::{MemoryPackReaderLimits, MemoryPackSerializerOptions, ReaderLimit, StringEncoding};
 pub use reader::MemoryPackReader;
 pub use serializer::MemoryPackSerializer;
 pub use state::{MemoryPackReaderOptionalState, MemoryPackWriterOptionalState};
-pub use traits::{MemoryPackDeserialize, MemoryPackDeserializeZeroCopy, MemoryPackSerialize};
-pub use writer::MemoryPackWriter;
+pub use traits::{
+    MemoryPackDeserialize, MemoryPackDeserializeZeroCopy, MemoryPackSchema, MemoryPackSerialize,
+    MemoryPackUnmanaged,
+};
+pub use writer::{MemoryPackWriter, Reservation};
 
 #[cfg(not(feature = "nightly"))]
 pub use traits::{NullableString, NullableVec};
 
-pub use traits::MultiDimArray;
+pub use traits::{MultiDimArray, NullableObject};
 
 #[cfg(feature = "derive")]
 pub use memorypack_derive::MemoryPackable;
@@ -29,7 +46,7 @@ pub mod prelude {
     pub use crate::error::MemoryPackError;
     pub use crate::reader::MemoryPackReader;
     pub use crate::serializer::MemoryPackSerializer;
-    pub use crate::traits::{MemoryPackDeserialize, MemoryPackSerialize};
+    pub use crate::traits::{MemoryPackDeserialize, MemoryPackSchema, MemoryPackSerialize};
     pub use crate::writer::MemoryPackWriter;
 
     #[cfg(feature = "derive")]
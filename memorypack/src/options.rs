@@ -0,0 +1,114 @@
+/// How [`MemoryPackWriter::write_string`](crate::MemoryPackWriter::write_string) encodes strings.
+///
+/// Readers accept both encodings regardless of this setting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum StringEncoding {
+    /// `!byte_len`, the UTF-16 length, then the UTF-8 bytes, like C# `StringEncoding.Utf8`
+    #[default]
+    Utf8,
+    /// The UTF-16 unit count, then the code units; the C# MemoryPack default
+    Utf16,
+}
+
+/// Settings applied by a [`MemoryPackWriter`](crate::MemoryPackWriter) while serializing
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct MemoryPackSerializerOptions {
+    pub string_encoding: StringEncoding,
+}
+
+impl MemoryPackSerializerOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_string_encoding(mut self, string_encoding: StringEncoding) -> Self {
+        self.string_encoding = string_encoding;
+        self
+    }
+}
+
+/// Bounds a [`MemoryPackReader`](crate::MemoryPackReader) enforces while decoding untrusted input.
+///
+/// Exceeding one fails with [`MemoryPackError::LimitExceeded`](crate::MemoryPackError::LimitExceeded).
+/// The defaults only bound nesting depth, so recursive types cannot overflow the stack.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MemoryPackReaderLimits {
+    /// Elements in one collection, map or multi-dimensional array
+    pub max_collection_length: usize,
+    /// Encoded bytes of one string
+    pub max_string_bytes: usize,
+    /// Bytes allocated for strings and collection storage over the whole read
+    pub max_allocated_bytes: usize,
+    /// Objects and unions nested inside each other
+    pub max_depth: usize,
+    /// Objects registered for circular references
+    pub max_reference_ids: usize,
+}
+
+impl MemoryPackReaderLimits {
+    pub const DEFAULT_MAX_DEPTH: usize = 256;
+
+    /// No limits at all, not even on nesting depth
+    pub const fn unlimited() -> Self {
+        Self {
+            max_collection_length: usize::MAX,
+            max_string_bytes: usize::MAX,
+            max_allocated_bytes: usize::MAX,
+            max_depth: usize::MAX,
+            max_reference_ids: usize::MAX,
+        }
+    }
+
+    pub fn with_max_collection_length(mut self, max_collection_length: usize) -> Self {
+        self.max_collection_length = max_collection_length;
+        self
+    }
+
+    pub fn with_max_string_bytes(mut self, max_string_bytes: usize) -> Self {
+        self.max_string_bytes = max_string_bytes;
+        self
+    }
+
+    pub fn with_max_allocated_bytes(mut self, max_allocated_bytes: usize) -> Self {
+        self.max_allocated_bytes = max_allocated_bytes;
+        self
+    }
+
+    pub fn with_max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    pub fn with_max_reference_ids(mut self, max_reference_ids: usize) -> Self {
+        self.max_reference_ids = max_reference_ids;
+        self
+    }
+}
+
+impl Default for MemoryPackReaderLimits {
+    fn default() -> Self {
+        Self::unlimited().with_max_depth(Self::DEFAULT_MAX_DEPTH)
+    }
+}
+
+/// The [`MemoryPackReaderLimits`] bound that was exceeded
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReaderLimit {
+    CollectionLength,
+    StringBytes,
+    AllocatedBytes,
+    Depth,
+    ReferenceIds,
+}
+
+impl std::fmt::Display for ReaderLimit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            ReaderLimit::CollectionLength => "collection length",
+            ReaderLimit::StringBytes => "string bytes",
+            ReaderLimit::AllocatedBytes => "allocated bytes",
+            ReaderLimit::Depth => "nesting depth",
+            ReaderLimit::ReferenceIds => "reference ids",
+        })
+    }
+}
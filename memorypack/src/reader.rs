@@ -1,52 +1,220 @@
 use crate::error::MemoryPackError;
+use crate::options::{MemoryPackReaderLimits, ReaderLimit};
 use crate::state::MemoryPackReaderOptionalState;
 
-use byteorder::{LittleEndian, ReadBytesExt};
 use simdutf8::basic;
-use std::io::{Cursor, Read, Seek, SeekFrom};
+use std::borrow::Cow;
+use std::io::{self, Read};
+
+enum Input<'a> {
+    Slice {
+        data: &'a [u8],
+        position: usize,
+    },
+    Stream {
+        source: &'a mut dyn Read,
+        position: u64,
+        peeked: Option<u8>,
+        scratch: Vec<u8>,
+    },
+}
+
+/// Elements preallocated for a collection read from a stream, whose remaining length is unknown
+const MAX_STREAM_PREALLOCATION: usize = 1024;
 
 pub struct MemoryPackReader<'a> {
-    pub(crate) cursor: Cursor<&'a [u8]>,
+    input: Input<'a>,
+    limits: MemoryPackReaderLimits,
+    depth: usize,
+    allocated: usize,
     pub optional_state: Option<MemoryPackReaderOptionalState>,
 }
 
 impl<'a> MemoryPackReader<'a> {
     pub fn new(data: &'a [u8]) -> Self {
         Self {
-            cursor: Cursor::new(data),
+            input: Input::Slice { data, position: 0 },
+            limits: MemoryPackReaderLimits::default(),
+            depth: 0,
+            allocated: 0,
             optional_state: None,
         }
     }
 
     pub fn new_with_state(data: &'a [u8]) -> Self {
         Self {
-            cursor: Cursor::new(data),
             optional_state: Some(MemoryPackReaderOptionalState::new()),
+            ..Self::new(data)
+        }
+    }
+
+    /// Create a reader that pulls bytes from `source` on demand.
+    ///
+    /// Exactly the bytes of the decoded values are consumed, so several payloads can be
+    /// read back to back; wrap unbuffered sources in a `BufReader`. Zero-copy reads
+    /// are not available on streaming readers.
+    pub fn from_stream<R: Read>(source: &'a mut R) -> Self {
+        Self {
+            input: Input::Stream {
+                source,
+                position: 0,
+                peeked: None,
+                scratch: Vec::new(),
+            },
+            limits: MemoryPackReaderLimits::default(),
+            depth: 0,
+            allocated: 0,
+            optional_state: None,
+        }
+    }
+
+    /// Decode under `limits` instead of [`MemoryPackReaderLimits::default`]
+    pub fn with_limits(mut self, limits: MemoryPackReaderLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    #[inline]
+    pub fn limits(&self) -> &MemoryPackReaderLimits {
+        &self.limits
+    }
+
+    #[inline]
+    pub fn set_limits(&mut self, limits: MemoryPackReaderLimits) {
+        self.limits = limits;
+    }
+
+    /// Circular reference state, created on first use and bound by the reference id limit
+    pub fn optional_state_mut(&mut self) -> &mut MemoryPackReaderOptionalState {
+        let state = self.optional_state.get_or_insert_with(MemoryPackReaderOptionalState::new);
+        state.max_reference_ids = self.limits.max_reference_ids;
+        state
+    }
+
+    /// Read a collection length header: `None` for null, otherwise a length within the limits
+    #[inline]
+    pub fn read_collection_length(&mut self) -> Result<Option<usize>, MemoryPackError> {
+        match self.read_i32()? {
+            -1 => Ok(None),
+            length if length < 0 => Err(MemoryPackError::InvalidLength(length)),
+            length => self.check_collection_length(length as usize).map(Some),
+        }
+    }
+
+    #[inline]
+    pub fn check_collection_length(&self, length: usize) -> Result<usize, MemoryPackError> {
+        check_limit(ReaderLimit::CollectionLength, length, self.limits.max_collection_length)?;
+        Ok(length)
+    }
+
+    /// Account for storing `length` values of `T`, returning how many are safe to preallocate
+    ///
+    /// The capacity never exceeds what the remaining input could hold, so a forged length
+    /// cannot allocate more than the payload itself before it is validated
+    #[inline]
+    pub fn reserve_elements<T>(&mut self, length: usize) -> Result<usize, MemoryPackError> {
+        self.charge_allocation(length.saturating_mul(std::mem::size_of::<T>()))?;
+
+        Ok(match &self.input {
+            Input::Slice { data, position } => length.min(data.len().saturating_sub(*position)),
+            Input::Stream { .. } => length.min(MAX_STREAM_PREALLOCATION),
+        })
+    }
+
+    #[inline]
+    fn charge_allocation(&mut self, bytes: usize) -> Result<(), MemoryPackError> {
+        let allocated = self.allocated.saturating_add(bytes);
+        check_limit(ReaderLimit::AllocatedBytes, allocated, self.limits.max_allocated_bytes)?;
+        self.allocated = allocated;
+        Ok(())
+    }
+
+    #[inline]
+    fn check_string_bytes(&mut self, byte_count: usize, owned: bool) -> Result<(), MemoryPackError> {
+        check_limit(ReaderLimit::StringBytes, byte_count, self.limits.max_string_bytes)?;
+        if owned {
+            self.charge_allocation(byte_count)?;
         }
+        Ok(())
+    }
+
+    /// Decode a nested object with `f`, one level deeper under the depth limit
+    #[inline]
+    pub fn nested<T>(
+        &mut self,
+        f: impl FnOnce(&mut Self) -> Result<T, MemoryPackError>,
+    ) -> Result<T, MemoryPackError> {
+        self.enter_depth()?;
+        let result = f(self);
+        self.leave_depth();
+        result
+    }
+
+    /// Decode the collection element at `index` with `f`, recording the index in the error path
+    #[inline]
+    pub fn element<T>(
+        &mut self,
+        index: usize,
+        f: impl FnOnce(&mut Self) -> Result<T, MemoryPackError>,
+    ) -> Result<T, MemoryPackError> {
+        f(self).map_err(|error| error.in_element(index, self.position()))
+    }
+
+    /// Decode the union variant `name` with `f`, recording the variant in the error path
+    #[inline]
+    pub fn variant<T>(
+        &mut self,
+        name: &'static str,
+        f: impl FnOnce(&mut Self) -> Result<T, MemoryPackError>,
+    ) -> Result<T, MemoryPackError> {
+        f(self).map_err(|error| error.in_variant(name, self.position()))
+    }
+
+    #[inline]
+    pub(crate) fn enter_depth(&mut self) -> Result<(), MemoryPackError> {
+        let depth = self.depth + 1;
+        check_limit(ReaderLimit::Depth, depth, self.limits.max_depth)?;
+        self.depth = depth;
+        Ok(())
+    }
+
+    #[inline]
+    pub(crate) fn leave_depth(&mut self) {
+        self.depth = self.depth.saturating_sub(1);
+    }
+
+    #[inline]
+    pub fn is_stream(&self) -> bool {
+        matches!(self.input, Input::Stream { .. })
     }
 
     pub fn read_string(&mut self) -> Result<String, MemoryPackError> {
+        Ok(self.read_nullable_string()?.unwrap_or_default())
+    }
+
+    pub fn read_nullable_string(&mut self) -> Result<Option<String>, MemoryPackError> {
         let length_or_marker = self.read_i32()?;
 
         if length_or_marker == -1 {
-            return Ok(String::new());
+            return Ok(None);
         }
 
         if length_or_marker < 0 {
-            return self.read_utf8_string(!length_or_marker as usize);
+            return self.read_utf8_string(!length_or_marker as usize).map(Some);
         }
 
         let char_count = length_or_marker as usize;
         if char_count == 0 {
-            return Ok(String::new());
+            return Ok(Some(String::new()));
         }
 
-        self.read_utf16_string(char_count)
+        self.read_utf16_string(char_count).map(Some)
     }
 
     fn read_utf8_string(&mut self, byte_count: usize) -> Result<String, MemoryPackError> {
+        self.check_string_bytes(byte_count, true)?;
         let _char_length = self.read_i32()?;
-        let slice = self.read_bytes(byte_count)?;
+        let slice = self.read_transient(byte_count)?;
 
         Ok(basic::from_utf8(slice)
             .map_err(|_| MemoryPackError::InvalidUtf8)?
@@ -68,8 +236,23 @@ impl<'a> MemoryPackReader<'a> {
         Err(MemoryPackError::Utf16NotSupportedForZeroCopy)
     }
 
+    /// Borrow UTF-8 strings from the input and decode UTF-16 strings into an owned copy
+    #[inline]
+    pub fn read_cow_str(&mut self) -> Result<Cow<'a, str>, MemoryPackError> {
+        if self.is_stream() {
+            return self.read_string().map(Cow::Owned);
+        }
+
+        match self.read_i32()? {
+            -1 | 0 => Ok(Cow::Borrowed("")),
+            marker if marker < 0 => self.read_utf8_str(!marker as usize).map(Cow::Borrowed),
+            char_count => self.read_utf16_string(char_count as usize).map(Cow::Owned),
+        }
+    }
+
     #[inline]
     fn read_utf8_str(&mut self, byte_count: usize) -> Result<&'a str, MemoryPackError> {
+        self.check_string_bytes(byte_count, false)?;
         let _char_length = self.read_i32()?;
         let slice = self.read_bytes(byte_count)?;
         
@@ -81,36 +264,144 @@ impl<'a> MemoryPackReader<'a> {
 
     #[inline]
     pub fn read_bytes(&mut self, length: usize) -> Result<&'a [u8], MemoryPackError> {
-        let pos = self.cursor.position() as usize;
-        let buffer = self.cursor.get_ref();
-
-        if pos + length > buffer.len() {
-            return Err(MemoryPackError::UnexpectedEndOfBuffer);
-        }
-
-        let slice = &buffer[pos..pos + length];
-        self.cursor.set_position((pos + length) as u64);
+        let Input::Slice { data, position } = &mut self.input else {
+            return Err(MemoryPackError::StreamNotSupportedForZeroCopy);
+        };
+
+        let slice = position
+            .checked_add(length)
+            .and_then(|end| data.get(*position..end))
+            .ok_or(MemoryPackError::UnexpectedEndOfBuffer)?;
+        *position += length;
         Ok(slice)
     }
 
     #[inline]
     pub fn read_bytes_vec(&mut self, length: usize) -> Result<Vec<u8>, MemoryPackError> {
-        Ok(self.read_bytes(length)?.to_vec())
+        if !self.is_stream() {
+            return Ok(self.read_bytes(length)?.to_vec());
+        }
+
+        let mut buffer = Vec::new();
+        self.read_stream_into(length, &mut buffer)?;
+        Ok(buffer)
     }
 
     #[inline]
     pub fn read_fixed_bytes<const N: usize>(&mut self) -> Result<[u8; N], MemoryPackError> {
+        self.read_array()
+    }
+
+    /// Read `length` bytes that only need to live until the next read
+    #[inline]
+    pub(crate) fn read_transient(&mut self, length: usize) -> Result<&[u8], MemoryPackError> {
+        if !self.is_stream() {
+            return self.read_bytes(length);
+        }
+
+        let Input::Stream { scratch, .. } = &mut self.input else {
+            unreachable!()
+        };
+        let mut buffer = std::mem::take(scratch);
+        buffer.clear();
+        let result = self.read_stream_into(length, &mut buffer);
+
+        let Input::Stream { scratch, .. } = &mut self.input else {
+            unreachable!()
+        };
+        *scratch = buffer;
+        result?;
+        Ok(scratch)
+    }
+
+    fn read_stream_into(&mut self, length: usize, buffer: &mut Vec<u8>) -> Result<(), MemoryPackError> {
+        let Input::Stream {
+            source,
+            position,
+            peeked,
+            ..
+        } = &mut self.input
+        else {
+            unreachable!()
+        };
+
+        if length == 0 {
+            return Ok(());
+        }
+
+        let mut remaining = length;
+        if let Some(byte) = peeked.take() {
+            buffer.push(byte);
+            remaining -= 1;
+        }
+
+        // Grow with the data actually received instead of trusting the length up front
+        let read = source
+            .take(remaining as u64)
+            .read_to_end(buffer)
+            .map_err(map_stream_error)?;
+        *position += (length - remaining + read) as u64;
+
+        if read < remaining {
+            return Err(MemoryPackError::UnexpectedEnd);
+        }
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn read_array<const N: usize>(&mut self) -> Result<[u8; N], MemoryPackError> {
         let mut buffer = [0u8; N];
-        self.cursor.read_exact(&mut buffer)?;
+        self.read_into(&mut buffer)?;
         Ok(buffer)
     }
 
+    /// Fill `buffer` with the next `buffer.len()` bytes
+    #[inline(always)]
+    pub fn read_into(&mut self, buffer: &mut [u8]) -> Result<(), MemoryPackError> {
+        let length = buffer.len();
+
+        match &mut self.input {
+            Input::Slice { data, position } => {
+                let bytes = position
+                    .checked_add(length)
+                    .and_then(|end| data.get(*position..end))
+                    .ok_or(MemoryPackError::UnexpectedEnd)?;
+                buffer.copy_from_slice(bytes);
+                *position += length;
+            }
+            Input::Stream {
+                source,
+                position,
+                peeked,
+                ..
+            } => {
+                let start = match peeked.take() {
+                    Some(byte) if length > 0 => {
+                        buffer[0] = byte;
+                        1
+                    }
+                    other => {
+                        *peeked = other;
+                        0
+                    }
+                };
+                source
+                    .read_exact(&mut buffer[start..])
+                    .map_err(map_stream_error)?;
+                *position += length as u64;
+            }
+        }
+
+        Ok(())
+    }
+
     #[inline]
     fn read_utf16_string(&mut self, char_count: usize) -> Result<String, MemoryPackError> {
-        let byte_count = char_count * 2;
-        let slice = self.read_bytes(byte_count)?;
+        let byte_count = char_count.saturating_mul(2);
+        self.check_string_bytes(byte_count, true)?;
+        let slice = self.read_transient(byte_count)?;
 
-        let mut result = String::with_capacity(char_count * 3);
+        let mut result = String::with_capacity(byte_count);
         let mut i = 0;
         while i < byte_count {
             let code_unit = u16::from_le_bytes([slice[i], slice[i + 1]]);
@@ -150,67 +441,67 @@ impl<'a> MemoryPackReader<'a> {
 
     #[inline(always)]
     pub fn read_bool(&mut self) -> Result<bool, MemoryPackError> {
-        Ok(self.cursor.read_u8()? == 1)
+        Ok(self.read_u8()? == 1)
     }
 
     #[inline(always)]
     pub fn read_i8(&mut self) -> Result<i8, MemoryPackError> {
-        Ok(self.cursor.read_i8()?)
+        Ok(i8::from_le_bytes(self.read_array()?))
     }
 
     #[inline(always)]
     pub fn read_u8(&mut self) -> Result<u8, MemoryPackError> {
-        Ok(self.cursor.read_u8()?)
+        Ok(u8::from_le_bytes(self.read_array()?))
     }
 
     #[inline(always)]
     pub fn read_i16(&mut self) -> Result<i16, MemoryPackError> {
-        Ok(self.cursor.read_i16::<LittleEndian>()?)
+        Ok(i16::from_le_bytes(self.read_array()?))
     }
 
     #[inline(always)]
     pub fn read_u16(&mut self) -> Result<u16, MemoryPackError> {
-        Ok(self.cursor.read_u16::<LittleEndian>()?)
+        Ok(u16::from_le_bytes(self.read_array()?))
     }
 
     #[inline(always)]
     pub fn read_i32(&mut self) -> Result<i32, MemoryPackError> {
-        Ok(self.cursor.read_i32::<LittleEndian>()?)
+        Ok(i32::from_le_bytes(self.read_array()?))
     }
 
     #[inline(always)]
     pub fn read_u32(&mut self) -> Result<u32, MemoryPackError> {
-        Ok(self.cursor.read_u32::<LittleEndian>()?)
+        Ok(u32::from_le_bytes(self.read_array()?))
     }
 
     #[inline(always)]
     pub fn read_i64(&mut self) -> Result<i64, MemoryPackError> {
-        Ok(self.cursor.read_i64::<LittleEndian>()?)
+        Ok(i64::from_le_bytes(self.read_array()?))
     }
 
     #[inline(always)]
     pub fn read_u64(&mut self) -> Result<u64, MemoryPackError> {
-        Ok(self.cursor.read_u64::<LittleEndian>()?)
+        Ok(u64::from_le_bytes(self.read_array()?))
     }
 
     #[inline(always)]
     pub fn read_f32(&mut self) -> Result<f32, MemoryPackError> {
-        Ok(self.cursor.read_f32::<LittleEndian>()?)
+        Ok(f32::from_le_bytes(self.read_array()?))
     }
 
     #[inline(always)]
     pub fn read_f64(&mut self) -> Result<f64, MemoryPackError> {
-        Ok(self.cursor.read_f64::<LittleEndian>()?)
+        Ok(f64::from_le_bytes(self.read_array()?))
     }
 
     #[inline(always)]
     pub fn read_i128(&mut self) -> Result<i128, MemoryPackError> {
-        Ok(self.cursor.read_i128::<LittleEndian>()?)
+        Ok(i128::from_le_bytes(self.read_array()?))
     }
 
     #[inline(always)]
     pub fn read_u128(&mut self) -> Result<u128, MemoryPackError> {
-        Ok(self.cursor.read_u128::<LittleEndian>()?)
+        Ok(u128::from_le_bytes(self.read_array()?))
     }
 
     #[inline(always)]
@@ -228,20 +519,85 @@ impl<'a> MemoryPackReader<'a> {
         ))
     }
 
+    /// Look at the next byte without consuming it
+    #[inline]
+    pub fn peek_u8(&mut self) -> Result<u8, MemoryPackError> {
+        match &mut self.input {
+            Input::Slice { data, position } => {
+                data.get(*position).copied().ok_or(MemoryPackError::UnexpectedEnd)
+            }
+            Input::Stream { source, peeked, .. } => {
+                if let Some(byte) = *peeked {
+                    return Ok(byte);
+                }
+                let mut byte = [0u8; 1];
+                source.read_exact(&mut byte).map_err(map_stream_error)?;
+                *peeked = Some(byte[0]);
+                Ok(byte[0])
+            }
+        }
+    }
+
     #[inline]
     pub fn skip(&mut self, n: usize) -> Result<(), MemoryPackError> {
-        self.cursor.seek(SeekFrom::Current(n as i64))?;
-        Ok(())
+        match &mut self.input {
+            Input::Slice { data, position } => {
+                *position = position
+                    .checked_add(n)
+                    .filter(|&end| end <= data.len())
+                    .ok_or(MemoryPackError::UnexpectedEnd)?;
+                Ok(())
+            }
+            Input::Stream { .. } => {
+                let mut remaining = n;
+                while remaining > 0 {
+                    let chunk = remaining.min(8 * 1024);
+                    self.read_transient(chunk)?;
+                    remaining -= chunk;
+                }
+                Ok(())
+            }
+        }
     }
 
+    /// Move back `n` bytes; only supported by slice-backed readers
     #[inline]
     pub fn rewind(&mut self, n: usize) -> Result<(), MemoryPackError> {
-        self.cursor.seek(SeekFrom::Current(-(n as i64)))?;
-        Ok(())
+        match &mut self.input {
+            Input::Slice { position, .. } => {
+                *position = position.checked_sub(n).ok_or_else(|| {
+                    MemoryPackError::DeserializationError("Cannot rewind before the start of the data".into())
+                })?;
+                Ok(())
+            }
+            Input::Stream { .. } => Err(MemoryPackError::DeserializationError(
+                "Streaming readers cannot rewind".into(),
+            )),
+        }
     }
 
     #[inline]
     pub fn position(&self) -> u64 {
-        self.cursor.position()
+        match &self.input {
+            Input::Slice { position, .. } => *position as u64,
+            Input::Stream { position, .. } => *position,
+        }
+    }
+}
+
+#[inline]
+fn check_limit(limit: ReaderLimit, value: usize, max: usize) -> Result<(), MemoryPackError> {
+    if value > max {
+        return Err(MemoryPackError::LimitExceeded(limit, max));
+    }
+    Ok(())
+}
+
+#[inline]
+fn map_stream_error(error: io::Error) -> MemoryPackError {
+    if error.kind() == io::ErrorKind::UnexpectedEof {
+        MemoryPackError::UnexpectedEnd
+    } else {
+        MemoryPackError::Io(error)
     }
 }
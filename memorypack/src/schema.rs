@@ -0,0 +1,238 @@
+//! Runtime descriptors of the MemoryPack wire layout, see [`MemoryPackSchema`](crate::MemoryPackSchema)
+
+use crate::traits::MemoryPackSchema;
+
+use std::cell::RefCell;
+use std::marker::PhantomData;
+
+/// Wire layout of a type
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Schema {
+    /// Writes nothing
+    Unit,
+    /// Fixed-size little-endian value
+    Primitive(Primitive),
+    /// UTF-8 or UTF-16 string behind a length header
+    String,
+    /// Length-prefixed elements
+    Collection(Box<Schema>),
+    /// `[T; N]`, written as a collection that must hold exactly `N` elements
+    Array(Box<Schema>, usize),
+    /// Length-prefixed key and value pairs
+    Map(Box<Schema>, Box<Schema>),
+    /// Rank, dimensions and the flattened elements of a rectangular array
+    MultiDimArray(Box<Schema>),
+    /// Values written back to back, like an unmanaged C# `ValueTuple`
+    Tuple(Vec<Schema>),
+    /// C# `Nullable<T>`: an `i32` has-value flag followed by the value, or its default for null
+    Nullable(Box<Schema>),
+    /// A reference type that may be null: strings and collections use the `-1` length,
+    /// objects and unions the null object header
+    NullableReference(Box<Schema>),
+    /// Value with a fixed layout of its own, named after its C# type
+    Known(KnownType),
+    Object(ObjectSchema),
+    Union(UnionSchema),
+    Enum(EnumSchema),
+    /// Written by a custom formatter, named by its type or `with` path, or a field whose type has
+    /// no schema, named by its `#[memorypack(schema = "Name")]` attribute or else its type
+    Custom(&'static str),
+    /// The named type, whose schema is already being described further up the tree
+    Recursive(&'static str),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Primitive {
+    Bool,
+    I8,
+    U8,
+    I16,
+    U16,
+    I32,
+    U32,
+    I64,
+    U64,
+    I128,
+    U128,
+    F32,
+    F64,
+    /// UTF-16 code unit, like C# `char`
+    Char,
+}
+
+impl Primitive {
+    /// Encoded size in bytes
+    pub const fn size(self) -> usize {
+        match self {
+            Self::Bool | Self::I8 | Self::U8 => 1,
+            Self::I16 | Self::U16 | Self::Char => 2,
+            Self::I32 | Self::U32 | Self::F32 => 4,
+            Self::I64 | Self::U64 | Self::F64 => 8,
+            Self::I128 | Self::U128 => 16,
+        }
+    }
+}
+
+/// Types with a dedicated layout from the extended type features
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum KnownType {
+    Guid,
+    Decimal,
+    Half,
+    BigInteger,
+    Uri,
+    TimeSpan,
+    DateTime,
+    DateTimeOffset,
+    TimeOnly,
+    DateOnly,
+    Vector2,
+    Vector3,
+    Vector4,
+    Quaternion,
+    Matrix3x2,
+    Matrix4x4,
+    Complex,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ObjectLayout {
+    /// Member count header followed by the members in order
+    Standard,
+    /// Member count and varint member lengths, so members can be added or removed
+    VersionTolerant,
+    /// Version-tolerant members plus a reference id, sharing repeated objects
+    Circular,
+    /// The members' raw bytes, like an unmanaged C# struct
+    Unmanaged,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ObjectSchema {
+    pub name: &'static str,
+    pub layout: ObjectLayout,
+    /// Serialized members, sorted by order
+    pub members: Vec<MemberSchema>,
+    /// Fields left out of the payload
+    pub skipped: Vec<&'static str>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MemberSchema {
+    /// Field name, or its position in a tuple struct
+    pub name: &'static str,
+    /// Position on the wire; version-tolerant and circular objects keep gaps between orders
+    pub order: usize,
+    /// The field's Rust type as written in the source
+    pub type_name: &'static str,
+    pub schema: Schema,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnionSchema {
+    pub name: &'static str,
+    pub variants: Vec<UnionVariant>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnionVariant {
+    pub name: &'static str,
+    pub tag: u16,
+    /// The wrapped type for single-field tuple variants, otherwise an object named
+    /// `{Union}{Variant}` holding the variant's fields
+    pub schema: Schema,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EnumSchema {
+    pub name: &'static str,
+    pub underlying: Primitive,
+    /// Combinable bit flags
+    pub flags: bool,
+    /// Named values; `repr(transparent)` integer wrappers have none
+    pub variants: Vec<EnumVariant>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EnumVariant {
+    pub name: &'static str,
+    pub value: i64,
+}
+
+thread_local! {
+    static DESCRIBING: RefCell<Vec<&'static str>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Pops the type being described, even if building its schema panics
+struct Describing;
+
+impl Drop for Describing {
+    fn drop(&mut self) {
+        DESCRIBING.with(|stack| stack.borrow_mut().pop());
+    }
+}
+
+/// Build the schema of a derived type, or [`Schema::Recursive`] if `type_id` is already being
+/// built, so self-referencing types describe a finite tree
+#[doc(hidden)]
+pub fn describe(type_id: &'static str, name: &'static str, build: impl FnOnce() -> Schema) -> Schema {
+    let recursive = DESCRIBING.with(|stack| {
+        let mut stack = stack.borrow_mut();
+        if stack.contains(&type_id) {
+            return true;
+        }
+        stack.push(type_id);
+        false
+    });
+    if recursive {
+        return Schema::Recursive(name);
+    }
+
+    let _describing = Describing;
+    build()
+}
+
+// Derived types describe each field through `(&FieldSchema::<T>::new()).field_schema(..)`: method
+// lookup picks `DescribedField` when `T: MemoryPackSchema` and only falls back to the
+// auto-referenced `OpaqueField` otherwise, so fields with hand-written impls still compile
+
+/// Schema of a field of a derived type
+#[doc(hidden)]
+pub struct FieldSchema<T: ?Sized>(PhantomData<fn() -> *const T>);
+
+impl<T: ?Sized> FieldSchema<T> {
+    #[inline]
+    pub fn new() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<T: ?Sized> Default for FieldSchema<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[doc(hidden)]
+pub trait DescribedField {
+    fn field_schema(&self, type_name: &'static str) -> Schema;
+}
+
+impl<T: MemoryPackSchema + ?Sized> DescribedField for FieldSchema<T> {
+    #[inline]
+    fn field_schema(&self, _type_name: &'static str) -> Schema {
+        T::schema()
+    }
+}
+
+#[doc(hidden)]
+pub trait OpaqueField {
+    fn field_schema(&self, type_name: &'static str) -> Schema;
+}
+
+impl<T: ?Sized> OpaqueField for &FieldSchema<T> {
+    #[inline]
+    fn field_schema(&self, type_name: &'static str) -> Schema {
+        Schema::Custom(type_name)
+    }
+}
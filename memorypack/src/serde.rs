@@ -0,0 +1,806 @@
+//! `serde` backend for the MemoryPack wire format.
+//!
+//! Types that only implement `serde::Serialize`/`serde::Deserialize` are mapped onto
+//! the same layout `#[derive(MemoryPackable)]` produces:
+//!
+//! - structs, tuple structs and unit structs are objects: a `u8` member count followed
+//!   by the fields in declaration order
+//! - sequences, maps and byte buffers are collections: an `i32` length followed by the
+//!   elements (`-1` decodes as empty)
+//! - tuples are their elements back to back, like `ValueTuple`; serde also hands fixed-size
+//!   arrays over as tuples, so `[T; N]` is written without the `i32` length header the
+//!   `MemoryPackSerialize` impl for arrays uses
+//! - options are nullables: an `i32` has-value flag, `1` followed by the value or `0`
+//!   on its own; unlike C# `Nullable<T>`, `None` is not followed by a default value
+//! - enums are unions: a `u8` tag with the variant index (`250` followed by a `u16` for
+//!   indices of 250 and above) and the variant's fields laid out as an object;
+//!   newtype variants are followed by the inner value as-is
+//!
+//! MemoryPack is not self-describing, so `deserialize_any` and `deserialize_ignored_any`
+//! are not supported.
+
+use crate::error::MemoryPackError;
+use crate::reader::MemoryPackReader;
+use crate::writer::{MemoryPackWriter, Reservation};
+
+use ::serde::de::{self, DeserializeSeed, IntoDeserializer, Visitor};
+use ::serde::ser::{self, Serialize};
+use std::fmt::Display;
+
+const NULL_OBJECT: u8 = 255;
+const WIDE_TAG: u8 = 250;
+const MAX_MEMBER_COUNT: usize = 249;
+
+impl ser::Error for MemoryPackError {
+    fn custom<T: Display>(msg: T) -> Self {
+        MemoryPackError::SerializationError(msg.to_string())
+    }
+}
+
+impl de::Error for MemoryPackError {
+    fn custom<T: Display>(msg: T) -> Self {
+        MemoryPackError::DeserializationError(msg.to_string())
+    }
+}
+
+/// Serialize a `serde::Serialize` value to MemoryPack bytes
+pub fn to_vec<T: ?Sized + Serialize>(value: &T) -> Result<Vec<u8>, MemoryPackError> {
+    let mut writer = MemoryPackWriter::with_capacity(64);
+    to_writer(value, &mut writer)?;
+    Ok(writer.into_bytes())
+}
+
+/// Serialize a `serde::Serialize` value into an existing writer
+pub fn to_writer<T: ?Sized + Serialize>(
+    value: &T,
+    writer: &mut MemoryPackWriter,
+) -> Result<(), MemoryPackError> {
+    value.serialize(&mut Serializer::new(writer))
+}
+
+/// Deserialize a `serde::Deserialize` value from MemoryPack bytes, borrowing where possible
+pub fn from_slice<'de, T: ::serde::Deserialize<'de>>(data: &'de [u8]) -> Result<T, MemoryPackError> {
+    let mut reader = MemoryPackReader::new(data);
+    from_reader(&mut reader).map_err(|error| error.at(reader.position()))
+}
+
+/// Deserialize a `serde::Deserialize` value from an existing reader
+pub fn from_reader<'de, T: ::serde::Deserialize<'de>>(
+    reader: &mut MemoryPackReader<'de>,
+) -> Result<T, MemoryPackError> {
+    T::deserialize(&mut Deserializer::new(reader))
+}
+
+#[inline]
+fn write_length(writer: &mut MemoryPackWriter, len: usize) -> Result<(), MemoryPackError> {
+    let len = i32::try_from(len).map_err(|_| {
+        MemoryPackError::SerializationError(format!("Collection length {len} exceeds i32::MAX"))
+    })?;
+    writer.write_i32(len)
+}
+
+#[inline]
+fn write_member_count(writer: &mut MemoryPackWriter, len: usize) -> Result<(), MemoryPackError> {
+    if len > MAX_MEMBER_COUNT {
+        return Err(MemoryPackError::SerializationError(format!(
+            "Objects can have at most {MAX_MEMBER_COUNT} members, found {len}"
+        )));
+    }
+    writer.write_u8(len as u8)
+}
+
+#[inline]
+fn write_union_tag(writer: &mut MemoryPackWriter, index: u32) -> Result<(), MemoryPackError> {
+    if index < WIDE_TAG as u32 {
+        return writer.write_u8(index as u8);
+    }
+
+    let index = u16::try_from(index).map_err(|_| {
+        MemoryPackError::SerializationError(format!("Union tag {index} exceeds u16::MAX"))
+    })?;
+    writer.write_u8(WIDE_TAG)?;
+    writer.write_u16(index)
+}
+
+/// `serde::Serializer` writing the MemoryPack layout into a [`MemoryPackWriter`]
+pub struct Serializer<'w, 'a> {
+    writer: &'w mut MemoryPackWriter<'a>,
+}
+
+impl<'w, 'a> Serializer<'w, 'a> {
+    pub fn new(writer: &'w mut MemoryPackWriter<'a>) -> Self {
+        Self { writer }
+    }
+}
+
+/// Serializer state for sequences, tuples, maps, structs and their enum variants
+pub struct Compound<'s, 'w, 'a> {
+    ser: &'s mut Serializer<'w, 'a>,
+    count: usize,
+    header: Option<Reservation>,
+}
+
+impl<'s, 'w, 'a> Compound<'s, 'w, 'a> {
+    fn new(ser: &'s mut Serializer<'w, 'a>) -> Self {
+        Self {
+            ser,
+            count: 0,
+            header: None,
+        }
+    }
+
+    /// Start a collection, reserving the length header when it is not known up front
+    fn collection(ser: &'s mut Serializer<'w, 'a>, len: Option<usize>) -> Result<Self, MemoryPackError> {
+        let header = match len {
+            Some(len) => {
+                write_length(ser.writer, len)?;
+                None
+            }
+            None => Some(ser.writer.reserve(4)?),
+        };
+        Ok(Self {
+            ser,
+            count: 0,
+            header,
+        })
+    }
+
+    #[inline]
+    fn element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), MemoryPackError> {
+        self.count += 1;
+        value.serialize(&mut *self.ser)
+    }
+
+    fn finish(self) -> Result<(), MemoryPackError> {
+        let Some(header) = self.header else {
+            return Ok(());
+        };
+        let count = i32::try_from(self.count).map_err(|_| {
+            MemoryPackError::SerializationError(format!("Collection length {} exceeds i32::MAX", self.count))
+        })?;
+        self.ser.writer.commit(header, &count.to_le_bytes())
+    }
+}
+
+impl<'s, 'w, 'a> ser::Serializer for &'s mut Serializer<'w, 'a> {
+    type Ok = ();
+    type Error = MemoryPackError;
+
+    type SerializeSeq = Compound<'s, 'w, 'a>;
+    type SerializeTuple = Compound<'s, 'w, 'a>;
+    type SerializeTupleStruct = Compound<'s, 'w, 'a>;
+    type SerializeTupleVariant = Compound<'s, 'w, 'a>;
+    type SerializeMap = Compound<'s, 'w, 'a>;
+    type SerializeStruct = Compound<'s, 'w, 'a>;
+    type SerializeStructVariant = Compound<'s, 'w, 'a>;
+
+    fn serialize_bool(self, v: bool) -> Result<(), MemoryPackError> {
+        self.writer.write_bool(v)
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<(), MemoryPackError> {
+        self.writer.write_i8(v)
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<(), MemoryPackError> {
+        self.writer.write_i16(v)
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<(), MemoryPackError> {
+        self.writer.write_i32(v)
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<(), MemoryPackError> {
+        self.writer.write_i64(v)
+    }
+
+    fn serialize_i128(self, v: i128) -> Result<(), MemoryPackError> {
+        self.writer.write_i128(v)
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<(), MemoryPackError> {
+        self.writer.write_u8(v)
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<(), MemoryPackError> {
+        self.writer.write_u16(v)
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<(), MemoryPackError> {
+        self.writer.write_u32(v)
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<(), MemoryPackError> {
+        self.writer.write_u64(v)
+    }
+
+    fn serialize_u128(self, v: u128) -> Result<(), MemoryPackError> {
+        self.writer.write_u128(v)
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<(), MemoryPackError> {
+        self.writer.write_f32(v)
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<(), MemoryPackError> {
+        self.writer.write_f64(v)
+    }
+
+    fn serialize_char(self, v: char) -> Result<(), MemoryPackError> {
+        self.writer.write_char(v)
+    }
+
+    fn serialize_str(self, v: &str) -> Result<(), MemoryPackError> {
+        self.writer.write_string(v)
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<(), MemoryPackError> {
+        write_length(self.writer, v.len())?;
+        self.writer.write_bytes(v)
+    }
+
+    fn serialize_none(self) -> Result<(), MemoryPackError> {
+        self.writer.write_i32(0)
+    }
+
+    fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<(), MemoryPackError> {
+        self.writer.write_i32(1)?;
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<(), MemoryPackError> {
+        Ok(())
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<(), MemoryPackError> {
+        write_member_count(self.writer, 0)
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        variant_index: u32,
+        _variant: &'static str,
+    ) -> Result<(), MemoryPackError> {
+        write_union_tag(self.writer, variant_index)?;
+        write_member_count(self.writer, 0)
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<(), MemoryPackError> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        variant_index: u32,
+        _variant: &'static str,
+        value: &T,
+    ) -> Result<(), MemoryPackError> {
+        write_union_tag(self.writer, variant_index)?;
+        value.serialize(self)
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq, MemoryPackError> {
+        Compound::collection(self, len)
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple, MemoryPackError> {
+        Ok(Compound::new(self))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleStruct, MemoryPackError> {
+        write_member_count(self.writer, len)?;
+        Ok(Compound::new(self))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        variant_index: u32,
+        _variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleVariant, MemoryPackError> {
+        write_union_tag(self.writer, variant_index)?;
+        write_member_count(self.writer, len)?;
+        Ok(Compound::new(self))
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<Self::SerializeMap, MemoryPackError> {
+        Compound::collection(self, len)
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStruct, MemoryPackError> {
+        write_member_count(self.writer, len)?;
+        Ok(Compound::new(self))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        variant_index: u32,
+        _variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStructVariant, MemoryPackError> {
+        write_union_tag(self.writer, variant_index)?;
+        write_member_count(self.writer, len)?;
+        Ok(Compound::new(self))
+    }
+
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+}
+
+impl ser::SerializeSeq for Compound<'_, '_, '_> {
+    type Ok = ();
+    type Error = MemoryPackError;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), MemoryPackError> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<(), MemoryPackError> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTuple for Compound<'_, '_, '_> {
+    type Ok = ();
+    type Error = MemoryPackError;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), MemoryPackError> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<(), MemoryPackError> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTupleStruct for Compound<'_, '_, '_> {
+    type Ok = ();
+    type Error = MemoryPackError;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), MemoryPackError> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<(), MemoryPackError> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTupleVariant for Compound<'_, '_, '_> {
+    type Ok = ();
+    type Error = MemoryPackError;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), MemoryPackError> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<(), MemoryPackError> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeMap for Compound<'_, '_, '_> {
+    type Ok = ();
+    type Error = MemoryPackError;
+
+    fn serialize_key<T: ?Sized + Serialize>(&mut self, key: &T) -> Result<(), MemoryPackError> {
+        self.element(key)
+    }
+
+    fn serialize_value<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), MemoryPackError> {
+        value.serialize(&mut *self.ser)
+    }
+
+    fn end(self) -> Result<(), MemoryPackError> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeStruct for Compound<'_, '_, '_> {
+    type Ok = ();
+    type Error = MemoryPackError;
+
+    fn serialize_field<T: ?Sized + Serialize>(
+        &mut self,
+        _key: &'static str,
+        value: &T,
+    ) -> Result<(), MemoryPackError> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<(), MemoryPackError> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeStructVariant for Compound<'_, '_, '_> {
+    type Ok = ();
+    type Error = MemoryPackError;
+
+    fn serialize_field<T: ?Sized + Serialize>(
+        &mut self,
+        _key: &'static str,
+        value: &T,
+    ) -> Result<(), MemoryPackError> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<(), MemoryPackError> {
+        self.finish()
+    }
+}
+
+/// `serde::Deserializer` reading the MemoryPack layout from a [`MemoryPackReader`]
+pub struct Deserializer<'r, 'de> {
+    reader: &'r mut MemoryPackReader<'de>,
+}
+
+impl<'r, 'de> Deserializer<'r, 'de> {
+    pub fn new(reader: &'r mut MemoryPackReader<'de>) -> Self {
+        Self { reader }
+    }
+
+    fn read_length(&mut self) -> Result<usize, MemoryPackError> {
+        Ok(self.reader.read_collection_length()?.unwrap_or(0))
+    }
+
+    /// Run `f` one level deeper, bounded by the reader's depth limit
+    fn nested<T>(
+        &mut self,
+        f: impl FnOnce(&mut Self) -> Result<T, MemoryPackError>,
+    ) -> Result<T, MemoryPackError> {
+        self.reader.enter_depth()?;
+        let result = f(self);
+        self.reader.leave_depth();
+        result
+    }
+
+    /// Read an object header, accepting at most `expected` members
+    fn read_member_count(&mut self, name: &'static str, expected: usize) -> Result<usize, MemoryPackError> {
+        match self.reader.read_u8()? {
+            NULL_OBJECT => Err(MemoryPackError::DeserializationError(
+                "Null objects cannot be deserialized through serde".into(),
+            )),
+            count if count as usize > expected => {
+                Err(MemoryPackError::MemberCountMismatch(name, expected, count as usize))
+            }
+            count => Ok(count as usize),
+        }
+    }
+
+    /// Name errors raised inside the object `name`; variants are read with an empty name
+    fn in_type<T>(&self, name: &'static str, result: Result<T, MemoryPackError>) -> Result<T, MemoryPackError> {
+        if name.is_empty() {
+            return result;
+        }
+        result.map_err(|error| error.in_type(name, self.reader.position()))
+    }
+
+    fn read_union_tag(&mut self) -> Result<u32, MemoryPackError> {
+        match self.reader.read_u8()? {
+            WIDE_TAG => Ok(self.reader.read_u16()? as u32),
+            NULL_OBJECT => Err(MemoryPackError::DeserializationError(
+                "Null unions cannot be deserialized through serde".into(),
+            )),
+            tag => Ok(tag as u32),
+        }
+    }
+
+    fn unsupported(what: &str) -> MemoryPackError {
+        MemoryPackError::DeserializationError(format!(
+            "MemoryPack is not self-describing and does not support {what}"
+        ))
+    }
+}
+
+impl<'de> de::Deserializer<'de> for &mut Deserializer<'_, 'de> {
+    type Error = MemoryPackError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, MemoryPackError> {
+        Err(Deserializer::unsupported("deserialize_any"))
+    }
+
+    fn deserialize_bool<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, MemoryPackError> {
+        visitor.visit_bool(self.reader.read_bool()?)
+    }
+
+    fn deserialize_i8<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, MemoryPackError> {
+        visitor.visit_i8(self.reader.read_i8()?)
+    }
+
+    fn deserialize_i16<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, MemoryPackError> {
+        visitor.visit_i16(self.reader.read_i16()?)
+    }
+
+    fn deserialize_i32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, MemoryPackError> {
+        visitor.visit_i32(self.reader.read_i32()?)
+    }
+
+    fn deserialize_i64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, MemoryPackError> {
+        visitor.visit_i64(self.reader.read_i64()?)
+    }
+
+    fn deserialize_i128<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, MemoryPackError> {
+        visitor.visit_i128(self.reader.read_i128()?)
+    }
+
+    fn deserialize_u8<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, MemoryPackError> {
+        visitor.visit_u8(self.reader.read_u8()?)
+    }
+
+    fn deserialize_u16<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, MemoryPackError> {
+        visitor.visit_u16(self.reader.read_u16()?)
+    }
+
+    fn deserialize_u32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, MemoryPackError> {
+        visitor.visit_u32(self.reader.read_u32()?)
+    }
+
+    fn deserialize_u64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, MemoryPackError> {
+        visitor.visit_u64(self.reader.read_u64()?)
+    }
+
+    fn deserialize_u128<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, MemoryPackError> {
+        visitor.visit_u128(self.reader.read_u128()?)
+    }
+
+    fn deserialize_f32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, MemoryPackError> {
+        visitor.visit_f32(self.reader.read_f32()?)
+    }
+
+    fn deserialize_f64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, MemoryPackError> {
+        visitor.visit_f64(self.reader.read_f64()?)
+    }
+
+    fn deserialize_char<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, MemoryPackError> {
+        visitor.visit_char(self.reader.read_char()?)
+    }
+
+    fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, MemoryPackError> {
+        if !self.reader.is_stream() {
+            // UTF-8 payloads can be borrowed straight from the input
+            let header = self.reader.read_i32()?;
+            self.reader.rewind(4)?;
+            if header < -1 {
+                return visitor.visit_borrowed_str(self.reader.read_str()?);
+            }
+        }
+        visitor.visit_string(self.reader.read_string()?)
+    }
+
+    fn deserialize_string<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, MemoryPackError> {
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, MemoryPackError> {
+        let len = self.read_length()?;
+        if self.reader.is_stream() {
+            return visitor.visit_byte_buf(self.reader.read_bytes_vec(len)?);
+        }
+        visitor.visit_borrowed_bytes(self.reader.read_bytes(len)?)
+    }
+
+    fn deserialize_byte_buf<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, MemoryPackError> {
+        self.deserialize_bytes(visitor)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, MemoryPackError> {
+        match self.reader.read_i32()? {
+            0 => visitor.visit_none(),
+            1 => visitor.visit_some(self),
+            flag => Err(MemoryPackError::DeserializationError(format!(
+                "Invalid has-value flag {flag} for an option"
+            ))),
+        }
+    }
+
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, MemoryPackError> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, MemoryPackError> {
+        self.read_member_count(name, 0)?;
+        visitor.visit_unit()
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, MemoryPackError> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, MemoryPackError> {
+        let remaining = self.read_length()?;
+        self.nested(|de| visitor.visit_seq(Access::new(de, remaining)))
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(
+        self,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, MemoryPackError> {
+        visitor.visit_seq(Access::new(self, len))
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, MemoryPackError> {
+        let remaining = self.read_member_count(name, len)?;
+        let result = self.nested(|de| visitor.visit_seq(Access::new(de, remaining)));
+        self.in_type(name, result)
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, MemoryPackError> {
+        let remaining = self.read_length()?;
+        self.nested(|de| visitor.visit_map(Access::new(de, remaining)))
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, MemoryPackError> {
+        let remaining = self.read_member_count(name, fields.len())?;
+        let result = self.nested(|de| visitor.visit_seq(Access { fields, ..Access::new(de, remaining) }));
+        self.in_type(name, result)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, MemoryPackError> {
+        self.nested(|de| visitor.visit_enum(de))
+    }
+
+    fn deserialize_identifier<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, MemoryPackError> {
+        Err(Deserializer::unsupported("identifiers"))
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, MemoryPackError> {
+        Err(Deserializer::unsupported("skipping unknown values"))
+    }
+
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+}
+
+/// Element access for sequences, tuples, maps and objects with a known element count
+struct Access<'a, 'r, 'de> {
+    de: &'a mut Deserializer<'r, 'de>,
+    remaining: usize,
+    index: usize,
+    /// Member names of a struct, used in error paths instead of element indices
+    fields: &'static [&'static str],
+}
+
+impl<'a, 'r, 'de> Access<'a, 'r, 'de> {
+    fn new(de: &'a mut Deserializer<'r, 'de>, remaining: usize) -> Self {
+        Self {
+            de,
+            remaining,
+            index: 0,
+            fields: &[],
+        }
+    }
+
+    fn locate<T>(&self, index: usize, result: Result<T, MemoryPackError>) -> Result<T, MemoryPackError> {
+        let position = self.de.reader.position();
+        result.map_err(|error| match self.fields.get(index) {
+            Some(name) => error.in_field(name, position),
+            None => error.in_element(index, position),
+        })
+    }
+}
+
+impl<'de> de::SeqAccess<'de> for Access<'_, '_, 'de> {
+    type Error = MemoryPackError;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, MemoryPackError> {
+        if self.remaining == 0 {
+            return Ok(None);
+        }
+        self.remaining -= 1;
+        self.index += 1;
+        let result = seed.deserialize(&mut *self.de).map(Some);
+        self.locate(self.index - 1, result)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.remaining)
+    }
+}
+
+impl<'de> de::MapAccess<'de> for Access<'_, '_, 'de> {
+    type Error = MemoryPackError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, MemoryPackError> {
+        if self.remaining == 0 {
+            return Ok(None);
+        }
+        self.remaining -= 1;
+        self.index += 1;
+        let result = seed.deserialize(&mut *self.de).map(Some);
+        self.locate(self.index - 1, result)
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, MemoryPackError> {
+        let result = seed.deserialize(&mut *self.de);
+        self.locate(self.index - 1, result)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.remaining)
+    }
+}
+
+impl<'de> de::EnumAccess<'de> for &mut Deserializer<'_, 'de> {
+    type Error = MemoryPackError;
+    type Variant = Self;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(self, seed: V) -> Result<(V::Value, Self), MemoryPackError> {
+        let tag = self.read_union_tag()?;
+        let variant = seed.deserialize(IntoDeserializer::<MemoryPackError>::into_deserializer(tag))?;
+        Ok((variant, self))
+    }
+}
+
+impl<'de> de::VariantAccess<'de> for &mut Deserializer<'_, 'de> {
+    type Error = MemoryPackError;
+
+    fn unit_variant(self) -> Result<(), MemoryPackError> {
+        self.read_member_count("", 0)?;
+        Ok(())
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value, MemoryPackError> {
+        seed.deserialize(self)
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(self, len: usize, visitor: V) -> Result<V::Value, MemoryPackError> {
+        de::Deserializer::deserialize_tuple_struct(self, "", len, visitor)
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, MemoryPackError> {
+        de::Deserializer::deserialize_struct(self, "", fields, visitor)
+    }
+}
@@ -1,8 +1,16 @@
 use crate::error::MemoryPackError;
+use crate::options::{MemoryPackReaderLimits, MemoryPackSerializerOptions};
 use crate::reader::MemoryPackReader;
 use crate::traits::{MemoryPackDeserialize, MemoryPackSerialize};
 use crate::writer::MemoryPackWriter;
 
+use std::io::{Read, Write};
+
+#[cfg(feature = "tokio")]
+use crate::codec::{DEFAULT_MAX_FRAME_LENGTH, FRAME_HEADER_LENGTH};
+#[cfg(feature = "tokio")]
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
 /// MemoryPack serializer
 pub struct MemoryPackSerializer;
 
@@ -15,6 +23,17 @@ impl MemoryPackSerializer {
         Ok(writer.into_bytes())
     }
 
+    /// Serialize a value to a byte vector using `options`, e.g. to select UTF-16 strings
+    #[inline]
+    pub fn serialize_with_options<T: MemoryPackSerialize>(
+        value: &T,
+        options: MemoryPackSerializerOptions,
+    ) -> Result<Vec<u8>, MemoryPackError> {
+        let mut writer = MemoryPackWriter::with_capacity(64).with_options(options);
+        value.serialize(&mut writer)?;
+        Ok(writer.into_bytes())
+    }
+
     /// Serialize a value to an existing writer
     #[inline]
     pub fn serialize_to<T: MemoryPackSerialize>(
@@ -24,11 +43,43 @@ impl MemoryPackSerializer {
         value.serialize(writer)
     }
 
+    /// Serialize a value into a caller-provided buffer, returning the number of bytes written
+    #[inline]
+    pub fn serialize_to_slice<T: MemoryPackSerialize>(
+        value: &T,
+        buffer: &mut [u8],
+    ) -> Result<usize, MemoryPackError> {
+        let mut writer = MemoryPackWriter::from_slice(buffer);
+        value.serialize(&mut writer)?;
+        Ok(writer.len())
+    }
+
+    /// Serialize a value into an `std::io::Write` sink, flushing it when done
+    #[inline]
+    pub fn serialize_to_stream<T: MemoryPackSerialize, W: Write>(
+        value: &T,
+        mut stream: W,
+    ) -> Result<(), MemoryPackError> {
+        let mut writer = MemoryPackWriter::from_stream(&mut stream);
+        value.serialize(&mut writer)?;
+        writer.flush()
+    }
+
     /// Deserialize a value from bytes
     #[inline]
     pub fn deserialize<T: MemoryPackDeserialize>(data: &[u8]) -> Result<T, MemoryPackError> {
         let mut reader = MemoryPackReader::new(data);
-        T::deserialize(&mut reader)
+        T::deserialize(&mut reader).map_err(|error| error.at(reader.position()))
+    }
+
+    /// Deserialize a value from untrusted bytes under `limits`
+    #[inline]
+    pub fn deserialize_with_limits<T: MemoryPackDeserialize>(
+        data: &[u8],
+        limits: MemoryPackReaderLimits,
+    ) -> Result<T, MemoryPackError> {
+        let mut reader = MemoryPackReader::new(data).with_limits(limits);
+        T::deserialize(&mut reader).map_err(|error| error.at(reader.position()))
     }
 
     /// Deserialize a value from an existing reader
@@ -36,7 +87,16 @@ impl MemoryPackSerializer {
     pub fn deserialize_from<T: MemoryPackDeserialize>(
         reader: &mut MemoryPackReader,
     ) -> Result<T, MemoryPackError> {
-        T::deserialize(reader)
+        T::deserialize(reader).map_err(|error| error.at(reader.position()))
+    }
+
+    /// Deserialize a value from an `std::io::Read` source, consuming only its bytes
+    #[inline]
+    pub fn deserialize_from_stream<T: MemoryPackDeserialize, R: Read>(
+        mut stream: R,
+    ) -> Result<T, MemoryPackError> {
+        let mut reader = MemoryPackReader::from_stream(&mut stream);
+        T::deserialize(&mut reader).map_err(|error| error.at(reader.position()))
     }
 
     /// Deserialize a value with zero-copy
@@ -46,6 +106,81 @@ impl MemoryPackSerializer {
         T: crate::traits::MemoryPackDeserializeZeroCopy<'a>,
     {
         let mut reader = MemoryPackReader::new(data);
-        T::deserialize(&mut reader)
+        T::deserialize(&mut reader).map_err(|error| error.at(reader.position()))
+    }
+
+    /// Serialize a value as a length-prefixed frame into an async sink
+    #[cfg(feature = "tokio")]
+    pub async fn serialize_to_async<T: MemoryPackSerialize, W: AsyncWrite + Unpin>(
+        value: &T,
+        mut stream: W,
+    ) -> Result<(), MemoryPackError> {
+        // Finish the frame before awaiting so the writer never lives inside the future
+        let frame = {
+            let mut writer = MemoryPackWriter::with_capacity(64);
+            let header = writer.reserve(FRAME_HEADER_LENGTH)?;
+            value.serialize(&mut writer)?;
+
+            let length = writer.len() - FRAME_HEADER_LENGTH;
+            let length = i32::try_from(length)
+                .map_err(|_| MemoryPackError::SerializationError("Frame exceeds i32::MAX bytes".into()))?;
+            writer.commit(header, &length.to_le_bytes())?;
+            writer.into_bytes()
+        };
+
+        stream.write_all(&frame).await?;
+        stream.flush().await?;
+        Ok(())
+    }
+
+    /// Deserialize a value from one length-prefixed frame read from an async source, rejecting
+    /// frames over [`DEFAULT_MAX_FRAME_LENGTH`](crate::codec::DEFAULT_MAX_FRAME_LENGTH) bytes
+    #[cfg(feature = "tokio")]
+    pub async fn deserialize_from_async<T: MemoryPackDeserialize, R: AsyncRead + Unpin>(
+        stream: R,
+    ) -> Result<T, MemoryPackError> {
+        Self::deserialize_from_async_with_limits(stream, DEFAULT_MAX_FRAME_LENGTH, MemoryPackReaderLimits::default())
+            .await
+    }
+
+    /// Deserialize a value from one length-prefixed frame of untrusted input, rejecting frames
+    /// over `max_frame_length` bytes and reading the payload under `limits`
+    #[cfg(feature = "tokio")]
+    pub async fn deserialize_from_async_with_limits<T: MemoryPackDeserialize, R: AsyncRead + Unpin>(
+        mut stream: R,
+        max_frame_length: usize,
+        limits: MemoryPackReaderLimits,
+    ) -> Result<T, MemoryPackError> {
+        let mut header = [0u8; FRAME_HEADER_LENGTH];
+        stream.read_exact(&mut header).await.map_err(map_async_error)?;
+
+        let length = i32::from_le_bytes(header);
+        if length < 0 {
+            return Err(MemoryPackError::InvalidLength(length));
+        }
+        let length = length as usize;
+        if length > max_frame_length {
+            return Err(MemoryPackError::FrameTooLarge(length, max_frame_length));
+        }
+
+        let mut payload = Vec::with_capacity(length);
+        let read = (&mut stream)
+            .take(length as u64)
+            .read_to_end(&mut payload)
+            .await?;
+        if read < length {
+            return Err(MemoryPackError::UnexpectedEnd);
+        }
+
+        Self::deserialize_with_limits(&payload, limits)
+    }
+}
+
+#[cfg(feature = "tokio")]
+fn map_async_error(error: std::io::Error) -> MemoryPackError {
+    if error.kind() == std::io::ErrorKind::UnexpectedEof {
+        MemoryPackError::UnexpectedEnd
+    } else {
+        MemoryPackError::Io(error)
     }
 }
@@ -1,4 +1,5 @@
 use crate::error::MemoryPackError;
+use crate::options::ReaderLimit;
 use std::any::Any;
 use std::collections::HashMap;
 
@@ -42,12 +43,14 @@ impl Default for MemoryPackWriterOptionalState {
 
 pub struct MemoryPackReaderOptionalState {
     ref_to_object: HashMap<u32, Box<dyn Any>>,
+    pub(crate) max_reference_ids: usize,
 }
 
 impl MemoryPackReaderOptionalState {
     pub fn new() -> Self {
         Self {
             ref_to_object: HashMap::new(),
+            max_reference_ids: usize::MAX,
         }
     }
 
@@ -79,6 +82,12 @@ impl MemoryPackReaderOptionalState {
                 id
             )));
         }
+        if self.ref_to_object.len() >= self.max_reference_ids {
+            return Err(MemoryPackError::LimitExceeded(
+                ReaderLimit::ReferenceIds,
+                self.max_reference_ids,
+            ));
+        }
         self.ref_to_object.insert(id, Box::new(value));
         Ok(())
     }
@@ -1,8 +1,15 @@
 use crate::error::MemoryPackError;
 use crate::reader::MemoryPackReader;
-use crate::traits::{MemoryPackDeserialize, MemoryPackSerialize};
+#[cfg(feature = "nightly")]
+use crate::traits::options::{deserialize_nullable_vec, serialize_nullable_vec};
+use crate::traits::unmanaged::copy_unmanaged_vec;
+use crate::traits::{
+    MemoryPackDeserialize, MemoryPackDeserializeZeroCopy, MemoryPackSerialize, MemoryPackUnmanaged,
+};
 use crate::writer::MemoryPackWriter;
+use std::borrow::Cow;
 use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, LinkedList, VecDeque};
+use std::hash::{BuildHasher, Hash};
 
 #[cfg(feature = "hashbrown")]
 use hashbrown::HashMap as HashbrownHashMap;
@@ -12,13 +19,10 @@ use hashbrown::HashSet as HashbrownHashSet;
 #[cfg(feature = "ahash")]
 use ahash::{AHashMap, AHashSet};
 
+/// Read a collection length, treating null and empty alike
 #[inline(always)]
-fn validate_size(size: i32) -> Result<Option<usize>, MemoryPackError> {
-    match size {
-        -1 | 0 => Ok(None),
-        s if s < 0 => Err(MemoryPackError::InvalidLength(s)),
-        s => Ok(Some(s as usize)),
-    }
+fn read_size(reader: &mut MemoryPackReader) -> Result<Option<usize>, MemoryPackError> {
+    Ok(reader.read_collection_length()?.filter(|&len| len > 0))
 }
 
 #[inline(always)]
@@ -29,58 +33,162 @@ fn write_collection_header(
     writer.write_i32(len as i32)
 }
 
-impl<T: MemoryPackSerialize> MemoryPackSerialize for Vec<T> {
+impl<T: MemoryPackSerialize> MemoryPackSerialize for [T] {
     #[inline(always)]
     fn serialize(&self, writer: &mut MemoryPackWriter) -> Result<(), MemoryPackError> {
         write_collection_header(writer, self.len())?;
-        for item in self.iter() {
-            item.serialize(writer)?;
+        T::serialize_slice(self, writer)
+    }
+}
+
+impl<T: MemoryPackSerialize> MemoryPackSerialize for &[T] {
+    #[inline(always)]
+    fn serialize(&self, writer: &mut MemoryPackWriter) -> Result<(), MemoryPackError> {
+        (**self).serialize(writer)
+    }
+}
+
+impl<T: MemoryPackSerialize + Clone> MemoryPackSerialize for Cow<'_, [T]> {
+    #[inline(always)]
+    fn serialize(&self, writer: &mut MemoryPackWriter) -> Result<(), MemoryPackError> {
+        (**self).serialize(writer)
+    }
+}
+
+impl<T: MemoryPackDeserialize + Clone> MemoryPackDeserialize for Cow<'_, [T]> {
+    #[inline(always)]
+    fn deserialize(reader: &mut MemoryPackReader) -> Result<Self, MemoryPackError> {
+        <Vec<T> as MemoryPackDeserialize>::deserialize(reader).map(Cow::Owned)
+    }
+}
+
+#[inline]
+fn read_unmanaged_bytes<'a, T: MemoryPackUnmanaged>(
+    reader: &mut MemoryPackReader<'a>,
+    len: usize,
+) -> Result<&'a [u8], MemoryPackError> {
+    let byte_len = len
+        .checked_mul(std::mem::size_of::<T>())
+        .ok_or(MemoryPackError::LengthOutOfRange(len as i64))?;
+    reader.read_bytes(byte_len)
+}
+
+/// View the `len` values in `bytes` in place, or `None` if they are misaligned for `T`
+#[inline]
+fn cast_aligned<T: MemoryPackUnmanaged>(bytes: &[u8], len: usize) -> Option<&[T]> {
+    if bytes.as_ptr().align_offset(std::mem::align_of::<T>()) != 0 {
+        return None;
+    }
+    // SAFETY: the bytes are aligned and unmanaged types accept any bit pattern
+    Some(unsafe { std::slice::from_raw_parts(bytes.as_ptr().cast::<T>(), len) })
+}
+
+impl<'a, T: MemoryPackUnmanaged> MemoryPackDeserializeZeroCopy<'a> for &'a [T] {
+    /// Fails with [`MemoryPackError::MisalignedForZeroCopy`] unless the data is aligned for `T`
+    #[inline]
+    fn deserialize(reader: &mut MemoryPackReader<'a>) -> Result<Self, MemoryPackError> {
+        let Some(len) = read_size(reader)? else {
+            return Ok(&[]);
+        };
+        if cfg!(target_endian = "big") && std::mem::size_of::<T>() > 1 {
+            return Err(MemoryPackError::MisalignedForZeroCopy(std::any::type_name::<T>()));
         }
-        Ok(())
+
+        let bytes = read_unmanaged_bytes::<T>(reader, len)?;
+        cast_aligned(bytes, len).ok_or(MemoryPackError::MisalignedForZeroCopy(std::any::type_name::<T>()))
+    }
+}
+
+impl<'a, T: MemoryPackUnmanaged + MemoryPackDeserialize> MemoryPackDeserializeZeroCopy<'a>
+    for Cow<'a, [T]>
+{
+    /// Borrows the input when it is suitably aligned and copies it otherwise
+    #[inline]
+    fn deserialize(reader: &mut MemoryPackReader<'a>) -> Result<Self, MemoryPackError> {
+        let Some(len) = read_size(reader)? else {
+            return Ok(Cow::Borrowed(&[]));
+        };
+        if reader.is_stream() || (cfg!(target_endian = "big") && std::mem::size_of::<T>() > 1) {
+            return T::deserialize_vec(reader, len).map(Cow::Owned);
+        }
+
+        let bytes = read_unmanaged_bytes::<T>(reader, len)?;
+        Ok(match cast_aligned(bytes, len) {
+            Some(items) => Cow::Borrowed(items),
+            None => Cow::Owned(copy_unmanaged_vec(bytes, len)),
+        })
+    }
+}
+
+impl<T: MemoryPackSerialize> MemoryPackSerialize for Vec<T> {
+    #[inline(always)]
+    fn serialize(&self, writer: &mut MemoryPackWriter) -> Result<(), MemoryPackError> {
+        self.as_slice().serialize(writer)
+    }
+
+    // Matches the `Option<Vec<T>>` specialization for pointers forwarding to this hook
+    #[cfg(feature = "nightly")]
+    #[inline]
+    fn serialize_option<F>(value: Option<&Self>, writer: &mut MemoryPackWriter, _: F) -> Result<(), MemoryPackError>
+    where
+        F: FnOnce(&mut MemoryPackWriter) -> Result<(), MemoryPackError>,
+    {
+        serialize_nullable_vec(value.map(Vec::as_slice), writer)
     }
 }
 
 impl<T: MemoryPackDeserialize> MemoryPackDeserialize for Vec<T> {
     #[inline(always)]
     fn deserialize(reader: &mut MemoryPackReader) -> Result<Self, MemoryPackError> {
-        let size = reader.read_i32()?;
-        match validate_size(size)? {
+        match read_size(reader)? {
             None => Ok(Vec::new()),
-            Some(capacity) => {
-                let mut result = Vec::with_capacity(capacity);
-                for _ in 0..capacity {
-                    result.push(T::deserialize(reader)?);
-                }
-                Ok(result)
-            }
+            Some(len) => T::deserialize_vec(reader, len),
         }
     }
+
+    #[cfg(feature = "nightly")]
+    #[inline]
+    fn deserialize_option(reader: &mut MemoryPackReader) -> Result<Option<Self>, MemoryPackError> {
+        deserialize_nullable_vec(reader)
+    }
+}
+
+impl<T: MemoryPackSerialize, const N: usize> MemoryPackSerialize for [T; N] {
+    #[inline(always)]
+    fn serialize(&self, writer: &mut MemoryPackWriter) -> Result<(), MemoryPackError> {
+        write_collection_header(writer, N)?;
+        T::serialize_slice(self, writer)
+    }
+}
+
+impl<T: MemoryPackDeserialize, const N: usize> MemoryPackDeserialize for [T; N] {
+    #[inline(always)]
+    fn deserialize(reader: &mut MemoryPackReader) -> Result<Self, MemoryPackError> {
+        let size = reader.read_i32()?;
+        let length = if size == -1 { 0 } else { size };
+        if length < 0 || length as usize != N {
+            return Err(MemoryPackError::ArrayLengthMismatch(N, size));
+        }
+        T::deserialize_array(reader)
+    }
 }
 
 impl<T: MemoryPackSerialize> MemoryPackSerialize for VecDeque<T> {
     #[inline(always)]
     fn serialize(&self, writer: &mut MemoryPackWriter) -> Result<(), MemoryPackError> {
         write_collection_header(writer, self.len())?;
-        for item in self.iter() {
-            item.serialize(writer)?;
-        }
-        Ok(())
+        let (front, back) = self.as_slices();
+        T::serialize_slice(front, writer)?;
+        T::serialize_slice(back, writer)
     }
 }
 
 impl<T: MemoryPackDeserialize> MemoryPackDeserialize for VecDeque<T> {
     #[inline(always)]
     fn deserialize(reader: &mut MemoryPackReader) -> Result<Self, MemoryPackError> {
-        let size = reader.read_i32()?;
-        match validate_size(size)? {
+        match read_size(reader)? {
             None => Ok(VecDeque::new()),
-            Some(capacity) => {
-                let mut result = VecDeque::with_capacity(capacity);
-                for _ in 0..capacity {
-                    result.push_back(T::deserialize(reader)?);
-                }
-                Ok(result)
-            }
+            Some(len) => T::deserialize_vec(reader, len).map(VecDeque::from),
         }
     }
 }
@@ -99,13 +207,13 @@ impl<T: MemoryPackSerialize> MemoryPackSerialize for LinkedList<T> {
 impl<T: MemoryPackDeserialize> MemoryPackDeserialize for LinkedList<T> {
     #[inline(always)]
     fn deserialize(reader: &mut MemoryPackReader) -> Result<Self, MemoryPackError> {
-        let size = reader.read_i32()?;
-        match validate_size(size)? {
+        match read_size(reader)? {
             None => Ok(LinkedList::new()),
-            Some(capacity) => {
+            Some(len) => {
+                reader.reserve_elements::<T>(len)?;
                 let mut result = LinkedList::new();
-                for _ in 0..capacity {
-                    result.push_back(T::deserialize(reader)?);
+                for i in 0..len {
+                    result.push_back(reader.element(i, T::deserialize)?);
                 }
                 Ok(result)
             }
@@ -113,7 +221,7 @@ impl<T: MemoryPackDeserialize> MemoryPackDeserialize for LinkedList<T> {
     }
 }
 
-impl<T: MemoryPackSerialize + Eq + std::hash::Hash> MemoryPackSerialize for HashSet<T> {
+impl<T: MemoryPackSerialize, S> MemoryPackSerialize for HashSet<T, S> {
     #[inline(always)]
     fn serialize(&self, writer: &mut MemoryPackWriter) -> Result<(), MemoryPackError> {
         write_collection_header(writer, self.len())?;
@@ -124,16 +232,20 @@ impl<T: MemoryPackSerialize + Eq + std::hash::Hash> MemoryPackSerialize for Hash
     }
 }
 
-impl<T: MemoryPackDeserialize + Eq + std::hash::Hash> MemoryPackDeserialize for HashSet<T> {
+impl<T, S> MemoryPackDeserialize for HashSet<T, S>
+where
+    T: MemoryPackDeserialize + Eq + Hash,
+    S: BuildHasher + Default,
+{
     #[inline(always)]
     fn deserialize(reader: &mut MemoryPackReader) -> Result<Self, MemoryPackError> {
-        let size = reader.read_i32()?;
-        match validate_size(size)? {
-            None => Ok(HashSet::new()),
-            Some(capacity) => {
-                let mut result = HashSet::with_capacity(capacity);
-                for _ in 0..capacity {
-                    result.insert(T::deserialize(reader)?);
+        match read_size(reader)? {
+            None => Ok(HashSet::with_hasher(S::default())),
+            Some(len) => {
+                let capacity = reader.reserve_elements::<T>(len)?;
+                let mut result = HashSet::with_capacity_and_hasher(capacity, S::default());
+                for i in 0..len {
+                    result.insert(reader.element(i, T::deserialize)?);
                 }
                 Ok(result)
             }
@@ -155,13 +267,13 @@ impl<T: MemoryPackSerialize + Ord> MemoryPackSerialize for BTreeSet<T> {
 impl<T: MemoryPackDeserialize + Ord> MemoryPackDeserialize for BTreeSet<T> {
     #[inline(always)]
     fn deserialize(reader: &mut MemoryPackReader) -> Result<Self, MemoryPackError> {
-        let size = reader.read_i32()?;
-        match validate_size(size)? {
+        match read_size(reader)? {
             None => Ok(BTreeSet::new()),
-            Some(capacity) => {
+            Some(len) => {
+                reader.reserve_elements::<T>(len)?;
                 let mut result = BTreeSet::new();
-                for _ in 0..capacity {
-                    result.insert(T::deserialize(reader)?);
+                for i in 0..len {
+                    result.insert(reader.element(i, T::deserialize)?);
                 }
                 Ok(result)
             }
@@ -169,100 +281,87 @@ impl<T: MemoryPackDeserialize + Ord> MemoryPackDeserialize for BTreeSet<T> {
     }
 }
 
-macro_rules! impl_std_hashmap {
-    ($key_type:ty) => {
-        impl<V: MemoryPackDeserialize + Default> MemoryPackDeserialize for HashMap<$key_type, V> {
-            #[inline(always)]
-            fn deserialize(reader: &mut MemoryPackReader) -> Result<Self, MemoryPackError> {
-                let count = reader.read_i32()?;
-                match validate_size(count)? {
-                    None => Ok(HashMap::new()),
-                    Some(capacity) => {
-                        let mut map = HashMap::with_capacity(capacity);
-                        for _ in 0..capacity {
-                            map.insert(<$key_type>::deserialize(reader)?, V::deserialize(reader)?);
-                        }
-                        Ok(map)
-                    }
-                }
-            }
+impl<K, V, S> MemoryPackSerialize for HashMap<K, V, S>
+where
+    K: MemoryPackSerialize,
+    V: MemoryPackSerialize,
+{
+    #[inline(always)]
+    fn serialize(&self, writer: &mut MemoryPackWriter) -> Result<(), MemoryPackError> {
+        write_collection_header(writer, self.len())?;
+        for (key, value) in self.iter() {
+            key.serialize(writer)?;
+            value.serialize(writer)?;
         }
+        Ok(())
+    }
+}
 
-        impl<V: MemoryPackSerialize> MemoryPackSerialize for HashMap<$key_type, V> {
-            #[inline(always)]
-            fn serialize(&self, writer: &mut MemoryPackWriter) -> Result<(), MemoryPackError> {
-                write_collection_header(writer, self.len())?;
-                for (key, value) in self.iter() {
-                    key.serialize(writer)?;
-                    value.serialize(writer)?;
+impl<K, V, S> MemoryPackDeserialize for HashMap<K, V, S>
+where
+    K: MemoryPackDeserialize + Eq + Hash,
+    V: MemoryPackDeserialize,
+    S: BuildHasher + Default,
+{
+    #[inline(always)]
+    fn deserialize(reader: &mut MemoryPackReader) -> Result<Self, MemoryPackError> {
+        match read_size(reader)? {
+            None => Ok(HashMap::with_hasher(S::default())),
+            Some(len) => {
+                let capacity = reader.reserve_elements::<(K, V)>(len)?;
+                let mut map = HashMap::with_capacity_and_hasher(capacity, S::default());
+                for i in 0..len {
+                    let (key, value) =
+                        reader.element(i, |reader| Ok((K::deserialize(reader)?, V::deserialize(reader)?)))?;
+                    map.insert(key, value);
                 }
-                Ok(())
+                Ok(map)
             }
         }
-    };
-}
-
-impl_std_hashmap!(String);
-impl_std_hashmap!(i8);
-impl_std_hashmap!(u8);
-impl_std_hashmap!(i16);
-impl_std_hashmap!(u16);
-impl_std_hashmap!(i32);
-impl_std_hashmap!(u32);
-impl_std_hashmap!(i64);
-impl_std_hashmap!(u64);
-impl_std_hashmap!(i128);
-impl_std_hashmap!(u128);
-impl_std_hashmap!(char);
-
-macro_rules! impl_btreemap {
-    ($key_type:ty) => {
-        impl<V: MemoryPackDeserialize + Default> MemoryPackDeserialize for BTreeMap<$key_type, V> {
-            #[inline(always)]
-            fn deserialize(reader: &mut MemoryPackReader) -> Result<Self, MemoryPackError> {
-                let count = reader.read_i32()?;
-                match validate_size(count)? {
-                    None => Ok(BTreeMap::new()),
-                    Some(capacity) => {
-                        let mut map = BTreeMap::new();
-                        for _ in 0..capacity {
-                            map.insert(<$key_type>::deserialize(reader)?, V::deserialize(reader)?);
-                        }
-                        Ok(map)
-                    }
-                }
-            }
+    }
+}
+
+impl<K, V> MemoryPackSerialize for BTreeMap<K, V>
+where
+    K: MemoryPackSerialize,
+    V: MemoryPackSerialize,
+{
+    #[inline(always)]
+    fn serialize(&self, writer: &mut MemoryPackWriter) -> Result<(), MemoryPackError> {
+        write_collection_header(writer, self.len())?;
+        for (key, value) in self.iter() {
+            key.serialize(writer)?;
+            value.serialize(writer)?;
         }
+        Ok(())
+    }
+}
 
-        impl<V: MemoryPackSerialize> MemoryPackSerialize for BTreeMap<$key_type, V> {
-            #[inline(always)]
-            fn serialize(&self, writer: &mut MemoryPackWriter) -> Result<(), MemoryPackError> {
-                write_collection_header(writer, self.len())?;
-                for (key, value) in self.iter() {
-                    key.serialize(writer)?;
-                    value.serialize(writer)?;
+impl<K, V> MemoryPackDeserialize for BTreeMap<K, V>
+where
+    K: MemoryPackDeserialize + Ord,
+    V: MemoryPackDeserialize,
+{
+    #[inline(always)]
+    fn deserialize(reader: &mut MemoryPackReader) -> Result<Self, MemoryPackError> {
+        match read_size(reader)? {
+            None => Ok(BTreeMap::new()),
+            Some(len) => {
+                reader.reserve_elements::<(K, V)>(len)?;
+                let mut map = BTreeMap::new();
+                for i in 0..len {
+                    let (key, value) =
+                        reader.element(i, |reader| Ok((K::deserialize(reader)?, V::deserialize(reader)?)))?;
+                    map.insert(key, value);
                 }
-                Ok(())
+                Ok(map)
             }
         }
-    };
-}
-
-impl_btreemap!(String);
-impl_btreemap!(i8);
-impl_btreemap!(u8);
-impl_btreemap!(i16);
-impl_btreemap!(u16);
-impl_btreemap!(i32);
-impl_btreemap!(u32);
-impl_btreemap!(i64);
-impl_btreemap!(u64);
-impl_btreemap!(i128);
-impl_btreemap!(u128);
-impl_btreemap!(char);
+    }
+}
 
 #[cfg(feature = "hashbrown")]
-impl<T: MemoryPackSerialize + Eq + std::hash::Hash> MemoryPackSerialize for HashbrownHashSet<T> {
+impl<T: MemoryPackSerialize, S> MemoryPackSerialize for HashbrownHashSet<T, S> {
     #[inline(always)]
     fn serialize(&self, writer: &mut MemoryPackWriter) -> Result<(), MemoryPackError> {
         write_collection_header(writer, self.len())?;
@@ -274,16 +373,20 @@ impl<T: MemoryPackSerialize + Eq + std::hash::Hash> MemoryPackSerialize for Hash
 }
 
 #[cfg(feature = "hashbrown")]
-impl<T: MemoryPackDeserialize + Eq + std::hash::Hash> MemoryPackDeserialize for HashbrownHashSet<T> {
+impl<T, S> MemoryPackDeserialize for HashbrownHashSet<T, S>
+where
+    T: MemoryPackDeserialize + Eq + Hash,
+    S: BuildHasher + Default,
+{
     #[inline(always)]
     fn deserialize(reader: &mut MemoryPackReader) -> Result<Self, MemoryPackError> {
-        let size = reader.read_i32()?;
-        match validate_size(size)? {
-            None => Ok(HashbrownHashSet::new()),
-            Some(capacity) => {
-                let mut result = HashbrownHashSet::with_capacity(capacity);
-                for _ in 0..capacity {
-                    result.insert(T::deserialize(reader)?);
+        match read_size(reader)? {
+            None => Ok(HashbrownHashSet::with_hasher(S::default())),
+            Some(len) => {
+                let capacity = reader.reserve_elements::<T>(len)?;
+                let mut result = HashbrownHashSet::with_capacity_and_hasher(capacity, S::default());
+                for i in 0..len {
+                    result.insert(reader.element(i, T::deserialize)?);
                 }
                 Ok(result)
             }
@@ -292,126 +395,91 @@ impl<T: MemoryPackDeserialize + Eq + std::hash::Hash> MemoryPackDeserialize for
 }
 
 #[cfg(feature = "hashbrown")]
-macro_rules! impl_hashbrown_hashmap {
-    ($key_type:ty) => {
-        impl<V: MemoryPackDeserialize + Default> MemoryPackDeserialize for HashbrownHashMap<$key_type, V> {
-            #[inline(always)]
-            fn deserialize(reader: &mut MemoryPackReader) -> Result<Self, MemoryPackError> {
-                let count = reader.read_i32()?;
-                match validate_size(count)? {
-                    None => Ok(HashbrownHashMap::new()),
-                    Some(capacity) => {
-                        let mut map = HashbrownHashMap::with_capacity(capacity);
-                        for _ in 0..capacity {
-                            map.insert(<$key_type>::deserialize(reader)?, V::deserialize(reader)?);
-                        }
-                        Ok(map)
-                    }
-                }
-            }
+impl<K, V, S> MemoryPackSerialize for HashbrownHashMap<K, V, S>
+where
+    K: MemoryPackSerialize,
+    V: MemoryPackSerialize,
+{
+    #[inline(always)]
+    fn serialize(&self, writer: &mut MemoryPackWriter) -> Result<(), MemoryPackError> {
+        write_collection_header(writer, self.len())?;
+        for (key, value) in self.iter() {
+            key.serialize(writer)?;
+            value.serialize(writer)?;
         }
+        Ok(())
+    }
+}
 
-        impl<V: MemoryPackSerialize> MemoryPackSerialize for HashbrownHashMap<$key_type, V> {
-            #[inline(always)]
-            fn serialize(&self, writer: &mut MemoryPackWriter) -> Result<(), MemoryPackError> {
-                write_collection_header(writer, self.len())?;
-                for (key, value) in self.iter() {
-                    key.serialize(writer)?;
-                    value.serialize(writer)?;
+#[cfg(feature = "hashbrown")]
+impl<K, V, S> MemoryPackDeserialize for HashbrownHashMap<K, V, S>
+where
+    K: MemoryPackDeserialize + Eq + Hash,
+    V: MemoryPackDeserialize,
+    S: BuildHasher + Default,
+{
+    #[inline(always)]
+    fn deserialize(reader: &mut MemoryPackReader) -> Result<Self, MemoryPackError> {
+        match read_size(reader)? {
+            None => Ok(HashbrownHashMap::with_hasher(S::default())),
+            Some(len) => {
+                let capacity = reader.reserve_elements::<(K, V)>(len)?;
+                let mut map = HashbrownHashMap::with_capacity_and_hasher(capacity, S::default());
+                for i in 0..len {
+                    let (key, value) =
+                        reader.element(i, |reader| Ok((K::deserialize(reader)?, V::deserialize(reader)?)))?;
+                    map.insert(key, value);
                 }
-                Ok(())
+                Ok(map)
             }
         }
-    };
+    }
 }
 
-#[cfg(feature = "hashbrown")]
-impl_hashbrown_hashmap!(String);
-#[cfg(feature = "hashbrown")]
-impl_hashbrown_hashmap!(i8);
-#[cfg(feature = "hashbrown")]
-impl_hashbrown_hashmap!(u8);
-#[cfg(feature = "hashbrown")]
-impl_hashbrown_hashmap!(i16);
-#[cfg(feature = "hashbrown")]
-impl_hashbrown_hashmap!(u16);
-#[cfg(feature = "hashbrown")]
-impl_hashbrown_hashmap!(i32);
-#[cfg(feature = "hashbrown")]
-impl_hashbrown_hashmap!(u32);
-#[cfg(feature = "hashbrown")]
-impl_hashbrown_hashmap!(i64);
-#[cfg(feature = "hashbrown")]
-impl_hashbrown_hashmap!(u64);
-#[cfg(feature = "hashbrown")]
-impl_hashbrown_hashmap!(i128);
-#[cfg(feature = "hashbrown")]
-impl_hashbrown_hashmap!(u128);
-#[cfg(feature = "hashbrown")]
-impl_hashbrown_hashmap!(char);
-
 
 #[cfg(feature = "ahash")]
-macro_rules! impl_ahash_hashmap {
-    ($key_type:ty) => {
-        impl<V: MemoryPackDeserialize + Default> MemoryPackDeserialize for AHashMap<$key_type, V> {
-            #[inline(always)]
-            fn deserialize(reader: &mut MemoryPackReader) -> Result<Self, MemoryPackError> {
-                let count = reader.read_i32()?;
-                match validate_size(count)? {
-                    None => Ok(AHashMap::new()),
-                    Some(capacity) => {
-                        let mut map = AHashMap::with_capacity(capacity);
-                        for _ in 0..capacity {
-                            map.insert(<$key_type>::deserialize(reader)?, V::deserialize(reader)?);
-                        }
-                        Ok(map)
-                    }
-                }
-            }
+impl<K, V> MemoryPackSerialize for AHashMap<K, V>
+where
+    K: MemoryPackSerialize,
+    V: MemoryPackSerialize,
+{
+    #[inline(always)]
+    fn serialize(&self, writer: &mut MemoryPackWriter) -> Result<(), MemoryPackError> {
+        write_collection_header(writer, self.len())?;
+        for (key, value) in self.iter() {
+            key.serialize(writer)?;
+            value.serialize(writer)?;
         }
+        Ok(())
+    }
+}
 
-        impl<V: MemoryPackSerialize> MemoryPackSerialize for AHashMap<$key_type, V> {
-            #[inline(always)]
-            fn serialize(&self, writer: &mut MemoryPackWriter) -> Result<(), MemoryPackError> {
-                write_collection_header(writer, self.len())?;
-                for (key, value) in self.iter() {
-                    key.serialize(writer)?;
-                    value.serialize(writer)?;
+#[cfg(feature = "ahash")]
+impl<K, V> MemoryPackDeserialize for AHashMap<K, V>
+where
+    K: MemoryPackDeserialize + Eq + Hash,
+    V: MemoryPackDeserialize,
+{
+    #[inline(always)]
+    fn deserialize(reader: &mut MemoryPackReader) -> Result<Self, MemoryPackError> {
+        match read_size(reader)? {
+            None => Ok(AHashMap::new()),
+            Some(len) => {
+                let capacity = reader.reserve_elements::<(K, V)>(len)?;
+                let mut map = AHashMap::with_capacity(capacity);
+                for i in 0..len {
+                    let (key, value) =
+                        reader.element(i, |reader| Ok((K::deserialize(reader)?, V::deserialize(reader)?)))?;
+                    map.insert(key, value);
                 }
-                Ok(())
+                Ok(map)
             }
         }
-    };
+    }
 }
 
 #[cfg(feature = "ahash")]
-impl_ahash_hashmap!(String);
-#[cfg(feature = "ahash")]
-impl_ahash_hashmap!(i8);
-#[cfg(feature = "ahash")]
-impl_ahash_hashmap!(u8);
-#[cfg(feature = "ahash")]
-impl_ahash_hashmap!(i16);
-#[cfg(feature = "ahash")]
-impl_ahash_hashmap!(u16);
-#[cfg(feature = "ahash")]
-impl_ahash_hashmap!(i32);
-#[cfg(feature = "ahash")]
-impl_ahash_hashmap!(u32);
-#[cfg(feature = "ahash")]
-impl_ahash_hashmap!(i64);
-#[cfg(feature = "ahash")]
-impl_ahash_hashmap!(u64);
-#[cfg(feature = "ahash")]
-impl_ahash_hashmap!(i128);
-#[cfg(feature = "ahash")]
-impl_ahash_hashmap!(u128);
-#[cfg(feature = "ahash")]
-impl_ahash_hashmap!(char);
-
-#[cfg(feature = "ahash")]
-impl<T: MemoryPackSerialize + Eq + std::hash::Hash> MemoryPackSerialize for AHashSet<T> {
+impl<T: MemoryPackSerialize> MemoryPackSerialize for AHashSet<T> {
     #[inline(always)]
     fn serialize(&self, writer: &mut MemoryPackWriter) -> Result<(), MemoryPackError> {
         write_collection_header(writer, self.len())?;
@@ -423,16 +491,16 @@ impl<T: MemoryPackSerialize + Eq + std::hash::Hash> MemoryPackSerialize for AHas
 }
 
 #[cfg(feature = "ahash")]
-impl<T: MemoryPackDeserialize + Eq + std::hash::Hash> MemoryPackDeserialize for AHashSet<T> {
+impl<T: MemoryPackDeserialize + Eq + Hash> MemoryPackDeserialize for AHashSet<T> {
     #[inline(always)]
     fn deserialize(reader: &mut MemoryPackReader) -> Result<Self, MemoryPackError> {
-        let size = reader.read_i32()?;
-        match validate_size(size)? {
+        match read_size(reader)? {
             None => Ok(AHashSet::new()),
-            Some(capacity) => {
+            Some(len) => {
+                let capacity = reader.reserve_elements::<T>(len)?;
                 let mut result = AHashSet::with_capacity(capacity);
-                for _ in 0..capacity {
-                    result.insert(T::deserialize(reader)?);
+                for i in 0..len {
+                    result.insert(reader.element(i, T::deserialize)?);
                 }
                 Ok(result)
             }
@@ -27,8 +27,10 @@ impl MemoryPackSerialize for chrono::TimeDelta {
 impl MemoryPackDeserialize for chrono::TimeDelta {
     #[inline(always)]
     fn deserialize(reader: &mut MemoryPackReader) -> Result<Self, MemoryPackError> {
-        let ticks = reader.read_i64()?;
-        Ok(chrono::TimeDelta::nanoseconds(ticks * TICKS_PER_NANOSECOND))
+        let nanos = reader.read_i64()?.checked_mul(TICKS_PER_NANOSECOND).ok_or_else(|| {
+            MemoryPackError::DeserializationError("TimeSpan out of range".into())
+        })?;
+        Ok(chrono::TimeDelta::nanoseconds(nanos))
     }
 }
 
@@ -84,9 +86,7 @@ impl MemoryPackSerialize for chrono::DateTime<chrono::FixedOffset> {
         let ticks = (unix_nanos / TICKS_PER_NANOSECOND) + DOTNET_EPOCH_TICKS;
 
         writer.write_i16(offset_minutes)?;
-        writer
-            .buffer
-            .extend_from_slice(&[0xFF, 0xFF, 0x00, 0x00, 0x00, 0x00]);
+        writer.write_bytes(&[0xFF, 0xFF, 0x00, 0x00, 0x00, 0x00])?;
         writer.write_i64(ticks)
     }
 }
@@ -99,7 +99,7 @@ impl MemoryPackDeserialize for chrono::DateTime<chrono::FixedOffset> {
         reader.read_fixed_bytes::<6>()?;
         let ticks = reader.read_i64()?;
 
-        let unix_nanos = (ticks - DOTNET_EPOCH_TICKS).saturating_mul(TICKS_PER_NANOSECOND);
+        let unix_nanos = ticks.saturating_sub(DOTNET_EPOCH_TICKS).saturating_mul(TICKS_PER_NANOSECOND);
         let offset_seconds = (offset_minutes as i32) * 60;
 
         let utc = chrono::DateTime::from_timestamp_nanos(unix_nanos);
@@ -124,8 +124,9 @@ impl MemoryPackSerialize for chrono::NaiveTime {
 impl MemoryPackDeserialize for chrono::NaiveTime {
     #[inline(always)]
     fn deserialize(reader: &mut MemoryPackReader) -> Result<Self, MemoryPackError> {
-        let ticks = reader.read_i64()?;
-        let total_nanos = ticks * TICKS_PER_NANOSECOND;
+        let total_nanos = reader.read_i64()?.checked_mul(TICKS_PER_NANOSECOND).ok_or_else(|| {
+            MemoryPackError::DeserializationError("Invalid time ticks".into())
+        })?;
         let secs = (total_nanos / 1_000_000_000) as u32;
         let nanos = (total_nanos % 1_000_000_000) as u32;
 
@@ -7,8 +7,7 @@ use crate::writer::MemoryPackWriter;
 impl MemoryPackSerialize for uuid::Uuid {
     #[inline(always)]
     fn serialize(&self, writer: &mut MemoryPackWriter) -> Result<(), MemoryPackError> {
-        writer.buffer.extend_from_slice(self.as_bytes());
-        Ok(())
+        writer.write_bytes(self.as_bytes())
     }
 }
 
@@ -44,10 +43,17 @@ impl MemoryPackDeserialize for rust_decimal::Decimal {
         let lo64 = reader.read_u64()?;
         
         let negative = (flags & 0x8000_0000) != 0;
-        let scale = ((flags >> 16) & 0xFF) as u32;
+        let scale = (flags >> 16) & 0xFF;
         let lo = lo64 as u32;
         let mid = (lo64 >> 32) as u32;
-        
+
+        if scale > rust_decimal::Decimal::MAX_SCALE {
+            return Err(MemoryPackError::DeserializationError(format!(
+                "Decimal scale {} exceeds the maximum of {}",
+                scale,
+                rust_decimal::Decimal::MAX_SCALE
+            )));
+        }
         Ok(rust_decimal::Decimal::from_parts(lo, mid, hi, negative, scale))
     }
 }
@@ -93,8 +99,7 @@ impl MemoryPackSerialize for num_bigint::BigInt {
         }
 
         writer.write_i32(bytes.len() as i32)?;
-        writer.buffer.extend_from_slice(&bytes);
-        Ok(())
+        writer.write_bytes(&bytes)
     }
 }
 
@@ -104,12 +109,12 @@ impl MemoryPackDeserialize for num_bigint::BigInt {
     fn deserialize(reader: &mut MemoryPackReader) -> Result<Self, MemoryPackError> {
         let len = reader.read_i32()?;
         if len < 0 {
-            return Err(MemoryPackError::DeserializationError(
-                "Negative length in BigInteger".into(),
-            ));
+            return Err(MemoryPackError::InvalidLength(len));
         }
 
-        let mut bytes = reader.read_bytes_vec(len as usize)?;
+        let len = reader.check_collection_length(len as usize)?;
+        reader.reserve_elements::<u8>(len)?;
+        let mut bytes = reader.read_bytes_vec(len)?;
         let is_negative = bytes.last().map_or(false, |&b| b & 0x80 != 0);
 
         if is_negative {
@@ -133,8 +138,7 @@ impl MemoryPackSerialize for num_bigint::BigUint {
     fn serialize(&self, writer: &mut MemoryPackWriter) -> Result<(), MemoryPackError> {
         let bytes = self.to_bytes_le();
         writer.write_i32(bytes.len() as i32)?;
-        writer.buffer.extend_from_slice(&bytes);
-        Ok(())
+        writer.write_bytes(&bytes)
     }
 }
 
@@ -144,13 +148,13 @@ impl MemoryPackDeserialize for num_bigint::BigUint {
     fn deserialize(reader: &mut MemoryPackReader) -> Result<Self, MemoryPackError> {
         let len = reader.read_i32()?;
         if len < 0 {
-            return Err(MemoryPackError::DeserializationError(
-                "Negative length in BigUint".into(),
-            ));
+            return Err(MemoryPackError::InvalidLength(len));
         }
 
+        let len = reader.check_collection_length(len as usize)?;
+        reader.reserve_elements::<u8>(len)?;
         Ok(num_bigint::BigUint::from_bytes_le(
-            &reader.read_bytes_vec(len as usize)?,
+            &reader.read_bytes_vec(len)?,
         ))
     }
 }
@@ -3,6 +3,25 @@ use crate::reader::MemoryPackReader;
 use crate::traits::{MemoryPackDeserialize, MemoryPackSerialize};
 use crate::writer::MemoryPackWriter;
 
+#[cfg(feature = "glam")]
+use crate::traits::MemoryPackUnmanaged;
+#[cfg(feature = "glam")]
+use crate::traits::unmanaged::{unmanaged_deserialize, unmanaged_serialize};
+
+// These glam types are plain `f32` components laid out in the same order they are serialized
+#[cfg(feature = "glam")]
+macro_rules! impl_glam_unmanaged {
+    ($($ty:ty => $components:expr),*) => {
+        $(
+            const _: () = assert!(std::mem::size_of::<$ty>() == $components * 4);
+            unsafe impl MemoryPackUnmanaged for $ty {}
+        )*
+    };
+}
+
+#[cfg(feature = "glam")]
+impl_glam_unmanaged!(glam::Vec2 => 2, glam::Vec3 => 3, glam::Vec4 => 4, glam::Quat => 4, glam::Mat4 => 16);
+
 #[cfg(feature = "num-complex")]
 impl MemoryPackSerialize for num_complex::Complex<f64> {
     #[inline(always)]
@@ -30,6 +49,8 @@ impl MemoryPackSerialize for glam::Vec2 {
         writer.write_f32(arr[0])?;
         writer.write_f32(arr[1])
     }
+
+    unmanaged_serialize!();
 }
 
 #[cfg(feature = "glam")]
@@ -41,6 +62,8 @@ impl MemoryPackDeserialize for glam::Vec2 {
             reader.read_f32()?,
         ]))
     }
+
+    unmanaged_deserialize!();
 }
 
 #[cfg(feature = "glam")]
@@ -52,6 +75,8 @@ impl MemoryPackSerialize for glam::Vec3 {
         writer.write_f32(arr[1])?;
         writer.write_f32(arr[2])
     }
+
+    unmanaged_serialize!();
 }
 
 #[cfg(feature = "glam")]
@@ -64,6 +89,8 @@ impl MemoryPackDeserialize for glam::Vec3 {
             reader.read_f32()?,
         ]))
     }
+
+    unmanaged_deserialize!();
 }
 
 #[cfg(feature = "glam")]
@@ -76,6 +103,8 @@ impl MemoryPackSerialize for glam::Vec4 {
         writer.write_f32(arr[2])?;
         writer.write_f32(arr[3])
     }
+
+    unmanaged_serialize!();
 }
 
 #[cfg(feature = "glam")]
@@ -89,6 +118,8 @@ impl MemoryPackDeserialize for glam::Vec4 {
             reader.read_f32()?,
         ]))
     }
+
+    unmanaged_deserialize!();
 }
 
 #[cfg(feature = "glam")]
@@ -101,6 +132,8 @@ impl MemoryPackSerialize for glam::Quat {
         writer.write_f32(arr[2])?;
         writer.write_f32(arr[3])
     }
+
+    unmanaged_serialize!();
 }
 
 #[cfg(feature = "glam")]
@@ -114,6 +147,8 @@ impl MemoryPackDeserialize for glam::Quat {
             reader.read_f32()?,
         ]))
     }
+
+    unmanaged_deserialize!();
 }
 
 #[cfg(feature = "glam")]
@@ -158,6 +193,8 @@ impl MemoryPackSerialize for glam::Mat4 {
         }
         Ok(())
     }
+
+    unmanaged_serialize!();
 }
 
 #[cfg(feature = "glam")]
@@ -170,4 +207,6 @@ impl MemoryPackDeserialize for glam::Mat4 {
         }
         Ok(glam::Mat4::from_cols_array(&arr))
     }
+
+    unmanaged_deserialize!();
 }
@@ -0,0 +1,48 @@
+use crate::error::MemoryPackError;
+
+use std::marker::PhantomData;
+
+// Older payloads can have fewer members than the type. Derived code fills those in through
+// `(&MissingMember::<T>::new()).fill(..)`: method lookup picks `DefaultMember` when `T: Default`
+// and only falls back to the auto-referenced `RequiredMember`, which errors, otherwise
+
+/// Value for a member missing from an older payload
+#[doc(hidden)]
+pub struct MissingMember<T>(PhantomData<fn() -> T>);
+
+impl<T> MissingMember<T> {
+    #[inline]
+    pub fn new() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<T> Default for MissingMember<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[doc(hidden)]
+pub trait DefaultMember<T> {
+    fn fill(&self, type_name: &'static str, total: usize, found: usize) -> Result<T, MemoryPackError>;
+}
+
+impl<T: Default> DefaultMember<T> for MissingMember<T> {
+    #[inline]
+    fn fill(&self, _type_name: &'static str, _total: usize, _found: usize) -> Result<T, MemoryPackError> {
+        Ok(T::default())
+    }
+}
+
+#[doc(hidden)]
+pub trait RequiredMember<T> {
+    fn fill(&self, type_name: &'static str, total: usize, found: usize) -> Result<T, MemoryPackError>;
+}
+
+impl<T> RequiredMember<T> for &MissingMember<T> {
+    #[inline]
+    fn fill(&self, type_name: &'static str, total: usize, found: usize) -> Result<T, MemoryPackError> {
+        Err(MemoryPackError::MemberCountMismatch(type_name, total, found))
+    }
+}
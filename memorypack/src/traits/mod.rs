@@ -1,10 +1,14 @@
 mod collections;
+mod missing;
 mod multidim;
 mod options;
 mod primitives;
+mod schema;
 mod smart_ptrs;
 mod strings;
 mod tuples;
+mod unmanaged;
+mod zero_copy;
 
 #[cfg(any(
     feature = "uuid",
@@ -22,21 +26,121 @@ mod math;
 
 #[allow(unused_imports)]
 pub use {
-    collections::*, multidim::*, options::*, primitives::*, smart_ptrs::*, strings::*, tuples::*,
+    collections::*, missing::*, multidim::*, options::*, primitives::*, smart_ptrs::*, strings::*, tuples::*,
+    unmanaged::*,
 };
 
 use crate::error::MemoryPackError;
+use crate::options::MemoryPackSerializerOptions;
 use crate::reader::MemoryPackReader;
+use crate::schema::Schema;
 use crate::writer::MemoryPackWriter;
 
 pub trait MemoryPackSerialize {
     fn serialize(&self, writer: &mut MemoryPackWriter) -> Result<(), MemoryPackError>;
+
+    /// Exact number of bytes [`serialize`](Self::serialize) writes with default options, computed without allocating
+    fn serialized_size(&self) -> Result<usize, MemoryPackError> {
+        self.serialized_size_with(MemoryPackSerializerOptions::default())
+    }
+
+    /// Exact number of bytes [`serialize`](Self::serialize) writes with `options`, e.g. UTF-16 strings
+    fn serialized_size_with(&self, options: MemoryPackSerializerOptions) -> Result<usize, MemoryPackError> {
+        let mut writer = MemoryPackWriter::counting(options);
+        self.serialize(&mut writer)?;
+        Ok(writer.len())
+    }
+
+    /// Serialize the elements of a collection body; unmanaged types override this with a bulk copy
+    #[doc(hidden)]
+    #[inline(always)]
+    fn serialize_slice(items: &[Self], writer: &mut MemoryPackWriter) -> Result<(), MemoryPackError>
+    where
+        Self: Sized,
+    {
+        for item in items {
+            item.serialize(writer)?;
+        }
+        Ok(())
+    }
+
+    /// Serialize `Option<Self>`, calling `write_default` to fill in the value after the `None` flag;
+    /// derived objects override this to write `None` as the null object marker
+    #[doc(hidden)]
+    #[inline]
+    fn serialize_option<F>(
+        value: Option<&Self>,
+        writer: &mut MemoryPackWriter,
+        write_default: F,
+    ) -> Result<(), MemoryPackError>
+    where
+        Self: Sized,
+        F: FnOnce(&mut MemoryPackWriter) -> Result<(), MemoryPackError>,
+    {
+        options::serialize_option_generic(value, writer, write_default)
+    }
 }
 
 pub trait MemoryPackDeserialize: Sized {
     fn deserialize(reader: &mut MemoryPackReader) -> Result<Self, MemoryPackError>;
+
+    /// Deserialize a collection body of `len` elements; unmanaged types override this with a bulk copy
+    #[doc(hidden)]
+    #[inline(always)]
+    fn deserialize_vec(reader: &mut MemoryPackReader, len: usize) -> Result<Vec<Self>, MemoryPackError> {
+        let mut items = Vec::with_capacity(reader.reserve_elements::<Self>(len)?);
+        for i in 0..len {
+            items.push(reader.element(i, Self::deserialize)?);
+        }
+        Ok(items)
+    }
+
+    /// Deserialize `N` consecutive elements; unmanaged types override this with a bulk copy
+    #[doc(hidden)]
+    #[inline]
+    fn deserialize_array<const N: usize>(
+        reader: &mut MemoryPackReader,
+    ) -> Result<[Self; N], MemoryPackError> {
+        let mut items = Vec::with_capacity(N);
+        for i in 0..N {
+            items.push(reader.element(i, Self::deserialize)?);
+        }
+        items
+            .try_into()
+            .map_err(|_| MemoryPackError::DeserializationError("Array length mismatch".into()))
+    }
+
+    /// Deserialize `Option<Self>`; derived objects override this to read the null object marker
+    #[doc(hidden)]
+    #[inline]
+    fn deserialize_option(reader: &mut MemoryPackReader) -> Result<Option<Self>, MemoryPackError> {
+        options::deserialize_option_generic(reader)
+    }
 }
 
 pub trait MemoryPackDeserializeZeroCopy<'a>: Sized {
     fn deserialize(reader: &mut MemoryPackReader<'a>) -> Result<Self, MemoryPackError>;
+
+    /// Deserialize `Option<Self>`; derived objects override this to read the null object marker
+    #[doc(hidden)]
+    #[inline]
+    fn deserialize_option(reader: &mut MemoryPackReader<'a>) -> Result<Option<Self>, MemoryPackError> {
+        let has_value = reader.read_i32()?;
+        let value = Self::deserialize(reader)?;
+        Ok((has_value != 0).then_some(value))
+    }
+}
+
+/// Describes the wire layout of a type; derived for every `MemoryPackable` type. Fields whose
+/// type has hand-written impls without one are described as [`Schema::Custom`] named after the
+/// type, or after `#[memorypack(schema = "Name")]` if given
+pub trait MemoryPackSchema {
+    fn schema() -> Schema;
+
+    /// Schema of `Option<Self>`; overridden alongside `serialize_option` to match its layout
+    #[doc(hidden)]
+    #[inline]
+    fn option_schema() -> Schema {
+        Schema::Nullable(Box::new(Self::schema()))
+    }
 }
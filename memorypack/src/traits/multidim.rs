@@ -10,6 +10,9 @@ pub struct MultiDimArray<T> {
 }
 
 impl<T> MultiDimArray<T> {
+    /// # Panics
+    ///
+    /// If `data.len()` is not the product of `dimensions`; see [`try_new`](Self::try_new)
     #[inline]
     pub fn new(dimensions: Vec<usize>, data: Vec<T>) -> Self {
         let total: usize = dimensions.iter().product();
@@ -21,6 +24,21 @@ impl<T> MultiDimArray<T> {
         Self { dimensions, data }
     }
 
+    /// Like [`new`](Self::new), but fails instead of panicking on a length mismatch
+    pub fn try_new(dimensions: Vec<usize>, data: Vec<T>) -> Result<Self, MemoryPackError> {
+        let total = dimensions
+            .iter()
+            .try_fold(1usize, |total, &dim| total.checked_mul(dim));
+        if total != Some(data.len()) {
+            return Err(MemoryPackError::DeserializationError(format!(
+                "Data length {} does not match dimensions {:?}",
+                data.len(),
+                dimensions
+            )));
+        }
+        Ok(Self { dimensions, data })
+    }
+
     #[inline]
     pub fn rank(&self) -> usize {
         self.dimensions.len()
@@ -43,11 +61,7 @@ impl<T: MemoryPackSerialize> MemoryPackSerialize for MultiDimArray<T> {
 
         writer.write_i32(self.total_elements() as i32)?;
 
-        for item in &self.data {
-            item.serialize(writer)?;
-        }
-
-        Ok(())
+        T::serialize_slice(&self.data, writer)
     }
 }
 
@@ -69,7 +83,7 @@ impl<T: MemoryPackDeserialize> MemoryPackDeserialize for MultiDimArray<T> {
             if dim < 0 {
                 return Err(MemoryPackError::InvalidLength(dim));
             }
-            dimensions.push(dim as usize);
+            dimensions.push(reader.check_collection_length(dim as usize)?);
         }
 
         let total = reader.read_i32()?;
@@ -77,14 +91,9 @@ impl<T: MemoryPackDeserialize> MemoryPackDeserialize for MultiDimArray<T> {
             return Err(MemoryPackError::InvalidLength(total));
         }
 
-        let total_usize = total as usize;
-        let mut data = Vec::with_capacity(total_usize);
-
-        for _ in 0..total_usize {
-            data.push(T::deserialize(reader)?);
-        }
+        let data = T::deserialize_vec(reader, reader.check_collection_length(total as usize)?)?;
 
-        Ok(MultiDimArray { dimensions, data })
+        MultiDimArray::try_new(dimensions, data)
     }
 }
 
@@ -1,12 +1,15 @@
 use crate::error::MemoryPackError;
 use crate::reader::MemoryPackReader;
-use crate::traits::{MemoryPackDeserialize, MemoryPackSerialize};
+use crate::traits::{MemoryPackDeserialize, MemoryPackDeserializeZeroCopy, MemoryPackSerialize};
 use crate::writer::MemoryPackWriter;
 
+pub(crate) const NULL_OBJECT: u8 = 255;
+
 #[inline]
-pub(super) fn serialize_option_generic<T: MemoryPackSerialize + Default>(
-    opt: &Option<T>,
+pub(super) fn serialize_option_generic<T: MemoryPackSerialize>(
+    opt: Option<&T>,
     writer: &mut MemoryPackWriter,
+    write_default: impl FnOnce(&mut MemoryPackWriter) -> Result<(), MemoryPackError>,
 ) -> Result<(), MemoryPackError> {
     match opt {
         Some(value) => {
@@ -15,7 +18,7 @@ pub(super) fn serialize_option_generic<T: MemoryPackSerialize + Default>(
         }
         None => {
             writer.write_i32(0)?;
-            T::default().serialize(writer)?;
+            write_default(writer)?;
         }
     }
     Ok(())
@@ -34,9 +37,80 @@ pub(super) fn deserialize_option_generic<T: MemoryPackDeserialize>(
     }
 }
 
+/// Write `Option<T>` like a nullable C# class: the null object marker for `None`, the value as-is otherwise
+#[inline]
+pub fn serialize_nullable_object<T: MemoryPackSerialize>(
+    opt: &Option<T>,
+    writer: &mut MemoryPackWriter,
+) -> Result<(), MemoryPackError> {
+    match opt {
+        Some(value) => value.serialize(writer),
+        None => writer.write_u8(NULL_OBJECT),
+    }
+}
+
+/// Read `Option<T>` written by [`serialize_nullable_object`]
+#[inline]
+pub fn deserialize_nullable_object<T: MemoryPackDeserialize>(
+    reader: &mut MemoryPackReader,
+) -> Result<Option<T>, MemoryPackError> {
+    if reader.peek_u8()? == NULL_OBJECT {
+        reader.read_u8()?;
+        return Ok(None);
+    }
+    T::deserialize(reader).map(Some)
+}
+
+/// Read `Option<T>` written by [`serialize_nullable_object`], borrowing from the input
+#[inline]
+pub fn deserialize_nullable_object_zero_copy<'a, T: MemoryPackDeserializeZeroCopy<'a>>(
+    reader: &mut MemoryPackReader<'a>,
+) -> Result<Option<T>, MemoryPackError> {
+    if reader.peek_u8()? == NULL_OBJECT {
+        reader.read_u8()?;
+        return Ok(None);
+    }
+    T::deserialize(reader).map(Some)
+}
+
+/// `Option<T>` encoded as a nullable C# class instead of a `Nullable<T>` struct, so `T` needs no `Default`
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct NullableObject<T>(pub Option<T>);
+
+impl<T: MemoryPackSerialize> MemoryPackSerialize for NullableObject<T> {
+    #[inline]
+    fn serialize(&self, writer: &mut MemoryPackWriter) -> Result<(), MemoryPackError> {
+        serialize_nullable_object(&self.0, writer)
+    }
+}
+
+impl<T: MemoryPackDeserialize> MemoryPackDeserialize for NullableObject<T> {
+    #[inline]
+    fn deserialize(reader: &mut MemoryPackReader) -> Result<Self, MemoryPackError> {
+        Ok(NullableObject(deserialize_nullable_object(reader)?))
+    }
+}
+
+impl<'a, T: MemoryPackDeserializeZeroCopy<'a>> MemoryPackDeserializeZeroCopy<'a> for NullableObject<T> {
+    #[inline]
+    fn deserialize(reader: &mut MemoryPackReader<'a>) -> Result<Self, MemoryPackError> {
+        Ok(NullableObject(deserialize_nullable_object_zero_copy(reader)?))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T: serde::Serialize> serde::Serialize for NullableObject<T> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serde::Serialize::serialize(&self.0, serializer)
+    }
+}
+
 #[inline]
 pub(super) fn serialize_nullable_string(
-    opt: &Option<String>,
+    opt: Option<&str>,
     writer: &mut MemoryPackWriter,
 ) -> Result<(), MemoryPackError> {
     match opt {
@@ -49,27 +123,18 @@ pub(super) fn serialize_nullable_string(
 pub(super) fn deserialize_nullable_string(
     reader: &mut MemoryPackReader,
 ) -> Result<Option<String>, MemoryPackError> {
-    let len = reader.read_i32()?;
-    if len == -1 {
-        Ok(None)
-    } else {
-        reader.rewind(4)?;
-        Ok(Some(String::deserialize(reader)?))
-    }
+    reader.read_nullable_string()
 }
 
 #[inline]
 pub(super) fn serialize_nullable_vec<T: MemoryPackSerialize>(
-    opt: &Option<Vec<T>>,
+    opt: Option<&[T]>,
     writer: &mut MemoryPackWriter,
 ) -> Result<(), MemoryPackError> {
     match opt {
         Some(vec) => {
             writer.write_i32(vec.len() as i32)?;
-            for item in vec.iter() {
-                item.serialize(writer)?;
-            }
-            Ok(())
+            T::serialize_slice(vec, writer)
         }
         None => writer.write_i32(-1),
     }
@@ -79,19 +144,10 @@ pub(super) fn serialize_nullable_vec<T: MemoryPackSerialize>(
 pub(super) fn deserialize_nullable_vec<T: MemoryPackDeserialize>(
     reader: &mut MemoryPackReader,
 ) -> Result<Option<Vec<T>>, MemoryPackError> {
-    let size = reader.read_i32()?;
-    if size == -1 {
-        return Ok(None);
-    }
-    if size < 0 {
-        return Err(MemoryPackError::InvalidLength(size));
-    }
-
-    let mut result = Vec::with_capacity(size as usize);
-    for _ in 0..size {
-        result.push(T::deserialize(reader)?);
+    match reader.read_collection_length()? {
+        Some(len) => T::deserialize_vec(reader, len).map(Some),
+        None => Ok(None),
     }
-    Ok(Some(result))
 }
 
 #[cfg(feature = "nightly")]
@@ -101,21 +157,21 @@ mod option_impls {
     impl<T: MemoryPackSerialize + Default> MemoryPackSerialize for Option<T> {
         #[inline]
         default fn serialize(&self, writer: &mut MemoryPackWriter) -> Result<(), MemoryPackError> {
-            serialize_option_generic(self, writer)
+            T::serialize_option(self.as_ref(), writer, |writer| T::default().serialize(writer))
         }
     }
 
     impl<T: MemoryPackDeserialize> MemoryPackDeserialize for Option<T> {
         #[inline]
         default fn deserialize(reader: &mut MemoryPackReader) -> Result<Self, MemoryPackError> {
-            deserialize_option_generic(reader)
+            T::deserialize_option(reader)
         }
     }
 
     impl MemoryPackSerialize for Option<String> {
         #[inline]
         fn serialize(&self, writer: &mut MemoryPackWriter) -> Result<(), MemoryPackError> {
-            serialize_nullable_string(self, writer)
+            serialize_nullable_string(self.as_deref(), writer)
         }
     }
 
@@ -129,7 +185,7 @@ mod option_impls {
     impl<T: MemoryPackSerialize> MemoryPackSerialize for Option<Vec<T>> {
         #[inline]
         fn serialize(&self, writer: &mut MemoryPackWriter) -> Result<(), MemoryPackError> {
-            serialize_nullable_vec(self, writer)
+            serialize_nullable_vec(self.as_deref(), writer)
         }
     }
 
@@ -148,14 +204,14 @@ mod option_impls {
     impl<T: MemoryPackSerialize + Default> MemoryPackSerialize for Option<T> {
         #[inline]
         fn serialize(&self, writer: &mut MemoryPackWriter) -> Result<(), MemoryPackError> {
-            serialize_option_generic(self, writer)
+            T::serialize_option(self.as_ref(), writer, |writer| T::default().serialize(writer))
         }
     }
 
     impl<T: MemoryPackDeserialize> MemoryPackDeserialize for Option<T> {
         #[inline]
         fn deserialize(reader: &mut MemoryPackReader) -> Result<Self, MemoryPackError> {
-            deserialize_option_generic(reader)
+            T::deserialize_option(reader)
         }
     }
 
@@ -165,7 +221,7 @@ mod option_impls {
     impl MemoryPackSerialize for NullableString {
         #[inline]
         fn serialize(&self, writer: &mut MemoryPackWriter) -> Result<(), MemoryPackError> {
-            serialize_nullable_string(&self.0, writer)
+            serialize_nullable_string(self.0.as_deref(), writer)
         }
     }
 
@@ -192,7 +248,7 @@ mod option_impls {
     impl<T: MemoryPackSerialize> MemoryPackSerialize for NullableVec<T> {
         #[inline]
         fn serialize(&self, writer: &mut MemoryPackWriter) -> Result<(), MemoryPackError> {
-            serialize_nullable_vec(&self.0, writer)
+            serialize_nullable_vec(self.0.as_deref(), writer)
         }
     }
 
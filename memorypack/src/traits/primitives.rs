@@ -1,5 +1,6 @@
 use crate::error::MemoryPackError;
 use crate::reader::MemoryPackReader;
+use crate::traits::unmanaged::{unmanaged_deserialize, unmanaged_serialize};
 use crate::traits::{MemoryPackDeserialize, MemoryPackSerialize};
 use crate::writer::MemoryPackWriter;
 
@@ -22,6 +23,8 @@ impl MemoryPackSerialize for i8 {
     fn serialize(&self, writer: &mut MemoryPackWriter) -> Result<(), MemoryPackError> {
         writer.write_i8(*self)
     }
+
+    unmanaged_serialize!();
 }
 
 impl MemoryPackDeserialize for i8 {
@@ -29,6 +32,8 @@ impl MemoryPackDeserialize for i8 {
     fn deserialize(reader: &mut MemoryPackReader) -> Result<Self, MemoryPackError> {
         reader.read_i8()
     }
+
+    unmanaged_deserialize!();
 }
 
 impl MemoryPackSerialize for u8 {
@@ -36,6 +41,8 @@ impl MemoryPackSerialize for u8 {
     fn serialize(&self, writer: &mut MemoryPackWriter) -> Result<(), MemoryPackError> {
         writer.write_u8(*self)
     }
+
+    unmanaged_serialize!();
 }
 
 impl MemoryPackDeserialize for u8 {
@@ -43,6 +50,8 @@ impl MemoryPackDeserialize for u8 {
     fn deserialize(reader: &mut MemoryPackReader) -> Result<Self, MemoryPackError> {
         reader.read_u8()
     }
+
+    unmanaged_deserialize!();
 }
 
 impl MemoryPackSerialize for i16 {
@@ -50,6 +59,8 @@ impl MemoryPackSerialize for i16 {
     fn serialize(&self, writer: &mut MemoryPackWriter) -> Result<(), MemoryPackError> {
         writer.write_i16(*self)
     }
+
+    unmanaged_serialize!();
 }
 
 impl MemoryPackDeserialize for i16 {
@@ -57,6 +68,8 @@ impl MemoryPackDeserialize for i16 {
     fn deserialize(reader: &mut MemoryPackReader) -> Result<Self, MemoryPackError> {
         reader.read_i16()
     }
+
+    unmanaged_deserialize!();
 }
 
 impl MemoryPackSerialize for u16 {
@@ -64,6 +77,8 @@ impl MemoryPackSerialize for u16 {
     fn serialize(&self, writer: &mut MemoryPackWriter) -> Result<(), MemoryPackError> {
         writer.write_u16(*self)
     }
+
+    unmanaged_serialize!();
 }
 
 impl MemoryPackDeserialize for u16 {
@@ -71,6 +86,8 @@ impl MemoryPackDeserialize for u16 {
     fn deserialize(reader: &mut MemoryPackReader) -> Result<Self, MemoryPackError> {
         reader.read_u16()
     }
+
+    unmanaged_deserialize!();
 }
 
 impl MemoryPackSerialize for i32 {
@@ -78,6 +95,8 @@ impl MemoryPackSerialize for i32 {
     fn serialize(&self, writer: &mut MemoryPackWriter) -> Result<(), MemoryPackError> {
         writer.write_i32(*self)
     }
+
+    unmanaged_serialize!();
 }
 
 impl MemoryPackDeserialize for i32 {
@@ -85,6 +104,8 @@ impl MemoryPackDeserialize for i32 {
     fn deserialize(reader: &mut MemoryPackReader) -> Result<Self, MemoryPackError> {
         reader.read_i32()
     }
+
+    unmanaged_deserialize!();
 }
 
 impl MemoryPackSerialize for u32 {
@@ -92,6 +113,8 @@ impl MemoryPackSerialize for u32 {
     fn serialize(&self, writer: &mut MemoryPackWriter) -> Result<(), MemoryPackError> {
         writer.write_u32(*self)
     }
+
+    unmanaged_serialize!();
 }
 
 impl MemoryPackDeserialize for u32 {
@@ -99,6 +122,8 @@ impl MemoryPackDeserialize for u32 {
     fn deserialize(reader: &mut MemoryPackReader) -> Result<Self, MemoryPackError> {
         reader.read_u32()
     }
+
+    unmanaged_deserialize!();
 }
 
 impl MemoryPackSerialize for i64 {
@@ -106,6 +131,8 @@ impl MemoryPackSerialize for i64 {
     fn serialize(&self, writer: &mut MemoryPackWriter) -> Result<(), MemoryPackError> {
         writer.write_i64(*self)
     }
+
+    unmanaged_serialize!();
 }
 
 impl MemoryPackDeserialize for i64 {
@@ -113,6 +140,8 @@ impl MemoryPackDeserialize for i64 {
     fn deserialize(reader: &mut MemoryPackReader) -> Result<Self, MemoryPackError> {
         reader.read_i64()
     }
+
+    unmanaged_deserialize!();
 }
 
 impl MemoryPackSerialize for u64 {
@@ -120,6 +149,8 @@ impl MemoryPackSerialize for u64 {
     fn serialize(&self, writer: &mut MemoryPackWriter) -> Result<(), MemoryPackError> {
         writer.write_u64(*self)
     }
+
+    unmanaged_serialize!();
 }
 
 impl MemoryPackDeserialize for u64 {
@@ -127,6 +158,8 @@ impl MemoryPackDeserialize for u64 {
     fn deserialize(reader: &mut MemoryPackReader) -> Result<Self, MemoryPackError> {
         reader.read_u64()
     }
+
+    unmanaged_deserialize!();
 }
 
 impl MemoryPackSerialize for f32 {
@@ -134,6 +167,8 @@ impl MemoryPackSerialize for f32 {
     fn serialize(&self, writer: &mut MemoryPackWriter) -> Result<(), MemoryPackError> {
         writer.write_f32(*self)
     }
+
+    unmanaged_serialize!();
 }
 
 impl MemoryPackDeserialize for f32 {
@@ -141,6 +176,8 @@ impl MemoryPackDeserialize for f32 {
     fn deserialize(reader: &mut MemoryPackReader) -> Result<Self, MemoryPackError> {
         reader.read_f32()
     }
+
+    unmanaged_deserialize!();
 }
 
 impl MemoryPackSerialize for f64 {
@@ -148,6 +185,8 @@ impl MemoryPackSerialize for f64 {
     fn serialize(&self, writer: &mut MemoryPackWriter) -> Result<(), MemoryPackError> {
         writer.write_f64(*self)
     }
+
+    unmanaged_serialize!();
 }
 
 impl MemoryPackDeserialize for f64 {
@@ -155,6 +194,8 @@ impl MemoryPackDeserialize for f64 {
     fn deserialize(reader: &mut MemoryPackReader) -> Result<Self, MemoryPackError> {
         reader.read_f64()
     }
+
+    unmanaged_deserialize!();
 }
 
 impl MemoryPackSerialize for i128 {
@@ -162,6 +203,8 @@ impl MemoryPackSerialize for i128 {
     fn serialize(&self, writer: &mut MemoryPackWriter) -> Result<(), MemoryPackError> {
         writer.write_i128(*self)
     }
+
+    unmanaged_serialize!();
 }
 
 impl MemoryPackDeserialize for i128 {
@@ -169,6 +212,8 @@ impl MemoryPackDeserialize for i128 {
     fn deserialize(reader: &mut MemoryPackReader) -> Result<Self, MemoryPackError> {
         reader.read_i128()
     }
+
+    unmanaged_deserialize!();
 }
 
 impl MemoryPackSerialize for u128 {
@@ -176,6 +221,8 @@ impl MemoryPackSerialize for u128 {
     fn serialize(&self, writer: &mut MemoryPackWriter) -> Result<(), MemoryPackError> {
         writer.write_u128(*self)
     }
+
+    unmanaged_serialize!();
 }
 
 impl MemoryPackDeserialize for u128 {
@@ -183,6 +230,8 @@ impl MemoryPackDeserialize for u128 {
     fn deserialize(reader: &mut MemoryPackReader) -> Result<Self, MemoryPackError> {
         reader.read_u128()
     }
+
+    unmanaged_deserialize!();
 }
 
 impl MemoryPackSerialize for char {
@@ -0,0 +1,281 @@
+use crate::formatter::Formatted;
+#[allow(unused_imports)]
+use crate::schema::{KnownType, Primitive, Schema};
+use crate::traits::{MemoryPackSchema, MultiDimArray, NullableObject};
+use std::borrow::Cow;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, LinkedList, VecDeque};
+use std::rc::Rc;
+use std::sync::Arc;
+
+#[cfg(not(feature = "nightly"))]
+use crate::traits::{NullableString, NullableVec};
+
+macro_rules! impl_schema {
+    ($($ty:ty => $schema:expr),+ $(,)?) => {
+        $(
+            impl MemoryPackSchema for $ty {
+                #[inline]
+                fn schema() -> Schema {
+                    $schema
+                }
+            }
+        )+
+    };
+}
+
+impl_schema!(
+    bool => Schema::Primitive(Primitive::Bool),
+    i8 => Schema::Primitive(Primitive::I8),
+    u8 => Schema::Primitive(Primitive::U8),
+    i16 => Schema::Primitive(Primitive::I16),
+    u16 => Schema::Primitive(Primitive::U16),
+    i32 => Schema::Primitive(Primitive::I32),
+    u32 => Schema::Primitive(Primitive::U32),
+    i64 => Schema::Primitive(Primitive::I64),
+    u64 => Schema::Primitive(Primitive::U64),
+    i128 => Schema::Primitive(Primitive::I128),
+    u128 => Schema::Primitive(Primitive::U128),
+    f32 => Schema::Primitive(Primitive::F32),
+    f64 => Schema::Primitive(Primitive::F64),
+    char => Schema::Primitive(Primitive::Char),
+    &str => Schema::String,
+    Cow<'_, str> => Schema::String,
+);
+
+impl MemoryPackSchema for String {
+    #[inline]
+    fn schema() -> Schema {
+        Schema::String
+    }
+
+    #[cfg(feature = "nightly")]
+    #[inline]
+    fn option_schema() -> Schema {
+        Schema::NullableReference(Box::new(Schema::String))
+    }
+}
+
+impl<T: MemoryPackSchema> MemoryPackSchema for Vec<T> {
+    #[inline]
+    fn schema() -> Schema {
+        Schema::Collection(Box::new(T::schema()))
+    }
+
+    #[cfg(feature = "nightly")]
+    #[inline]
+    fn option_schema() -> Schema {
+        Schema::NullableReference(Box::new(Self::schema()))
+    }
+}
+
+impl<T: MemoryPackSchema> MemoryPackSchema for Option<T> {
+    #[inline]
+    fn schema() -> Schema {
+        T::option_schema()
+    }
+}
+
+macro_rules! impl_collection_schema {
+    ($($ty:ty),+ $(,)?) => {
+        $(
+            impl<T: MemoryPackSchema> MemoryPackSchema for $ty {
+                #[inline]
+                fn schema() -> Schema {
+                    Schema::Collection(Box::new(T::schema()))
+                }
+            }
+        )+
+    };
+}
+
+impl_collection_schema!(&[T], VecDeque<T>, LinkedList<T>, BTreeSet<T>);
+
+impl<T: MemoryPackSchema + Clone> MemoryPackSchema for Cow<'_, [T]> {
+    #[inline]
+    fn schema() -> Schema {
+        Schema::Collection(Box::new(T::schema()))
+    }
+}
+
+impl<T: MemoryPackSchema, S> MemoryPackSchema for HashSet<T, S> {
+    #[inline]
+    fn schema() -> Schema {
+        Schema::Collection(Box::new(T::schema()))
+    }
+}
+
+impl<T: MemoryPackSchema, const N: usize> MemoryPackSchema for [T; N] {
+    #[inline]
+    fn schema() -> Schema {
+        Schema::Array(Box::new(T::schema()), N)
+    }
+}
+
+impl<K: MemoryPackSchema, V: MemoryPackSchema, S> MemoryPackSchema for HashMap<K, V, S> {
+    #[inline]
+    fn schema() -> Schema {
+        Schema::Map(Box::new(K::schema()), Box::new(V::schema()))
+    }
+}
+
+impl<K: MemoryPackSchema, V: MemoryPackSchema> MemoryPackSchema for BTreeMap<K, V> {
+    #[inline]
+    fn schema() -> Schema {
+        Schema::Map(Box::new(K::schema()), Box::new(V::schema()))
+    }
+}
+
+#[cfg(feature = "hashbrown")]
+impl<T: MemoryPackSchema, S> MemoryPackSchema for hashbrown::HashSet<T, S> {
+    #[inline]
+    fn schema() -> Schema {
+        Schema::Collection(Box::new(T::schema()))
+    }
+}
+
+#[cfg(feature = "hashbrown")]
+impl<K: MemoryPackSchema, V: MemoryPackSchema, S> MemoryPackSchema for hashbrown::HashMap<K, V, S> {
+    #[inline]
+    fn schema() -> Schema {
+        Schema::Map(Box::new(K::schema()), Box::new(V::schema()))
+    }
+}
+
+#[cfg(feature = "ahash")]
+impl<T: MemoryPackSchema> MemoryPackSchema for ahash::AHashSet<T> {
+    #[inline]
+    fn schema() -> Schema {
+        Schema::Collection(Box::new(T::schema()))
+    }
+}
+
+#[cfg(feature = "ahash")]
+impl<K: MemoryPackSchema, V: MemoryPackSchema> MemoryPackSchema for ahash::AHashMap<K, V> {
+    #[inline]
+    fn schema() -> Schema {
+        Schema::Map(Box::new(K::schema()), Box::new(V::schema()))
+    }
+}
+
+impl<T: MemoryPackSchema> MemoryPackSchema for MultiDimArray<T> {
+    #[inline]
+    fn schema() -> Schema {
+        Schema::MultiDimArray(Box::new(T::schema()))
+    }
+}
+
+impl<T: MemoryPackSchema> MemoryPackSchema for NullableObject<T> {
+    #[inline]
+    fn schema() -> Schema {
+        Schema::NullableReference(Box::new(T::schema()))
+    }
+}
+
+#[cfg(not(feature = "nightly"))]
+impl MemoryPackSchema for NullableString {
+    #[inline]
+    fn schema() -> Schema {
+        Schema::NullableReference(Box::new(Schema::String))
+    }
+}
+
+#[cfg(not(feature = "nightly"))]
+impl<T: MemoryPackSchema> MemoryPackSchema for NullableVec<T> {
+    #[inline]
+    fn schema() -> Schema {
+        Schema::NullableReference(Box::new(Vec::<T>::schema()))
+    }
+}
+
+// Smart pointers serialize their target as-is, `Option` of one included
+macro_rules! impl_pointer_schema {
+    ($($ty:ident),+) => {
+        $(
+            impl<T: MemoryPackSchema> MemoryPackSchema for $ty<T> {
+                #[inline]
+                fn schema() -> Schema {
+                    T::schema()
+                }
+
+                #[inline]
+                fn option_schema() -> Schema {
+                    T::option_schema()
+                }
+            }
+        )+
+    };
+}
+
+impl_pointer_schema!(Box, Rc, Arc);
+
+impl<T, F> MemoryPackSchema for Formatted<T, F> {
+    #[inline]
+    fn schema() -> Schema {
+        Schema::Custom(std::any::type_name::<F>())
+    }
+}
+
+macro_rules! impl_tuple_schema {
+    ($($T:ident),+) => {
+        impl<$($T: MemoryPackSchema),+> MemoryPackSchema for ($($T,)+) {
+            #[inline]
+            fn schema() -> Schema {
+                Schema::Tuple(vec![$($T::schema()),+])
+            }
+        }
+    };
+}
+
+impl_tuple_schema!(T1);
+impl_tuple_schema!(T1, T2);
+impl_tuple_schema!(T1, T2, T3);
+impl_tuple_schema!(T1, T2, T3, T4);
+impl_tuple_schema!(T1, T2, T3, T4, T5);
+impl_tuple_schema!(T1, T2, T3, T4, T5, T6);
+impl_tuple_schema!(T1, T2, T3, T4, T5, T6, T7);
+impl_tuple_schema!(T1, T2, T3, T4, T5, T6, T7, T8);
+impl_tuple_schema!(T1, T2, T3, T4, T5, T6, T7, T8, T9);
+impl_tuple_schema!(T1, T2, T3, T4, T5, T6, T7, T8, T9, T10);
+impl_tuple_schema!(T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11);
+impl_tuple_schema!(T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11, T12);
+
+#[cfg(feature = "uuid")]
+impl_schema!(uuid::Uuid => Schema::Known(KnownType::Guid));
+
+#[cfg(feature = "rust_decimal")]
+impl_schema!(rust_decimal::Decimal => Schema::Known(KnownType::Decimal));
+
+#[cfg(feature = "half")]
+impl_schema!(half::f16 => Schema::Known(KnownType::Half));
+
+#[cfg(feature = "num-bigint")]
+impl_schema!(
+    num_bigint::BigInt => Schema::Known(KnownType::BigInteger),
+    num_bigint::BigUint => Schema::Known(KnownType::BigInteger),
+);
+
+#[cfg(feature = "url")]
+impl_schema!(url::Url => Schema::Known(KnownType::Uri));
+
+#[cfg(feature = "chrono")]
+impl_schema!(
+    chrono::TimeDelta => Schema::Known(KnownType::TimeSpan),
+    chrono::DateTime<chrono::Utc> => Schema::Known(KnownType::DateTime),
+    chrono::DateTime<chrono::Local> => Schema::Known(KnownType::DateTime),
+    chrono::DateTime<chrono::FixedOffset> => Schema::Known(KnownType::DateTimeOffset),
+    chrono::NaiveTime => Schema::Known(KnownType::TimeOnly),
+    chrono::NaiveDate => Schema::Known(KnownType::DateOnly),
+);
+
+#[cfg(feature = "glam")]
+impl_schema!(
+    glam::Vec2 => Schema::Known(KnownType::Vector2),
+    glam::Vec3 => Schema::Known(KnownType::Vector3),
+    glam::Vec4 => Schema::Known(KnownType::Vector4),
+    glam::Quat => Schema::Known(KnownType::Quaternion),
+    glam::Mat3A => Schema::Known(KnownType::Matrix3x2),
+    glam::Mat4 => Schema::Known(KnownType::Matrix4x4),
+);
+
+#[cfg(feature = "num-complex")]
+impl_schema!(num_complex::Complex<f64> => Schema::Known(KnownType::Complex));
@@ -6,11 +6,25 @@ use crate::writer::MemoryPackWriter;
 use std::rc::Rc;
 use std::sync::Arc;
 
+// Pointers are transparent on the wire, so `Option` of one uses the layout of `Option<T>`
+
 impl<T: MemoryPackSerialize> MemoryPackSerialize for Box<T> {
     #[inline]
     fn serialize(&self, writer: &mut MemoryPackWriter) -> Result<(), MemoryPackError> {
         (**self).serialize(writer)
     }
+
+    #[inline]
+    fn serialize_option<F>(
+        value: Option<&Self>,
+        writer: &mut MemoryPackWriter,
+        write_default: F,
+    ) -> Result<(), MemoryPackError>
+    where
+        F: FnOnce(&mut MemoryPackWriter) -> Result<(), MemoryPackError>,
+    {
+        T::serialize_option(value.map(|pointer| &**pointer), writer, write_default)
+    }
 }
 
 impl<T: MemoryPackDeserialize> MemoryPackDeserialize for Box<T> {
@@ -18,6 +32,11 @@ impl<T: MemoryPackDeserialize> MemoryPackDeserialize for Box<T> {
     fn deserialize(reader: &mut MemoryPackReader) -> Result<Self, MemoryPackError> {
         Ok(Box::new(T::deserialize(reader)?))
     }
+
+    #[inline]
+    fn deserialize_option(reader: &mut MemoryPackReader) -> Result<Option<Self>, MemoryPackError> {
+        Ok(T::deserialize_option(reader)?.map(Box::new))
+    }
 }
 
 impl<T: MemoryPackSerialize> MemoryPackSerialize for Rc<T> {
@@ -25,6 +44,18 @@ impl<T: MemoryPackSerialize> MemoryPackSerialize for Rc<T> {
     fn serialize(&self, writer: &mut MemoryPackWriter) -> Result<(), MemoryPackError> {
         (**self).serialize(writer)
     }
+
+    #[inline]
+    fn serialize_option<F>(
+        value: Option<&Self>,
+        writer: &mut MemoryPackWriter,
+        write_default: F,
+    ) -> Result<(), MemoryPackError>
+    where
+        F: FnOnce(&mut MemoryPackWriter) -> Result<(), MemoryPackError>,
+    {
+        T::serialize_option(value.map(|pointer| &**pointer), writer, write_default)
+    }
 }
 
 impl<T: MemoryPackDeserialize> MemoryPackDeserialize for Rc<T> {
@@ -32,6 +63,11 @@ impl<T: MemoryPackDeserialize> MemoryPackDeserialize for Rc<T> {
     fn deserialize(reader: &mut MemoryPackReader) -> Result<Self, MemoryPackError> {
         Ok(Rc::new(T::deserialize(reader)?))
     }
+
+    #[inline]
+    fn deserialize_option(reader: &mut MemoryPackReader) -> Result<Option<Self>, MemoryPackError> {
+        Ok(T::deserialize_option(reader)?.map(Rc::new))
+    }
 }
 
 impl<T: MemoryPackSerialize> MemoryPackSerialize for Arc<T> {
@@ -39,6 +75,18 @@ impl<T: MemoryPackSerialize> MemoryPackSerialize for Arc<T> {
     fn serialize(&self, writer: &mut MemoryPackWriter) -> Result<(), MemoryPackError> {
         (**self).serialize(writer)
     }
+
+    #[inline]
+    fn serialize_option<F>(
+        value: Option<&Self>,
+        writer: &mut MemoryPackWriter,
+        write_default: F,
+    ) -> Result<(), MemoryPackError>
+    where
+        F: FnOnce(&mut MemoryPackWriter) -> Result<(), MemoryPackError>,
+    {
+        T::serialize_option(value.map(|pointer| &**pointer), writer, write_default)
+    }
 }
 
 impl<T: MemoryPackDeserialize> MemoryPackDeserialize for Arc<T> {
@@ -46,4 +94,9 @@ impl<T: MemoryPackDeserialize> MemoryPackDeserialize for Arc<T> {
     fn deserialize(reader: &mut MemoryPackReader) -> Result<Self, MemoryPackError> {
         Ok(Arc::new(T::deserialize(reader)?))
     }
+
+    #[inline]
+    fn deserialize_option(reader: &mut MemoryPackReader) -> Result<Option<Self>, MemoryPackError> {
+        Ok(T::deserialize_option(reader)?.map(Arc::new))
+    }
 }
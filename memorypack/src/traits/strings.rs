@@ -2,6 +2,8 @@ use std::borrow::Cow;
 
 use crate::error::MemoryPackError;
 use crate::reader::MemoryPackReader;
+#[cfg(feature = "nightly")]
+use crate::traits::options::{deserialize_nullable_string, serialize_nullable_string};
 use crate::traits::{MemoryPackDeserialize, MemoryPackDeserializeZeroCopy, MemoryPackSerialize};
 use crate::writer::MemoryPackWriter;
 
@@ -10,6 +12,16 @@ impl MemoryPackSerialize for String {
     fn serialize(&self, writer: &mut MemoryPackWriter) -> Result<(), MemoryPackError> {
         writer.write_string(self)
     }
+
+    // Matches the `Option<String>` specialization for pointers forwarding to this hook
+    #[cfg(feature = "nightly")]
+    #[inline]
+    fn serialize_option<F>(value: Option<&Self>, writer: &mut MemoryPackWriter, _: F) -> Result<(), MemoryPackError>
+    where
+        F: FnOnce(&mut MemoryPackWriter) -> Result<(), MemoryPackError>,
+    {
+        serialize_nullable_string(value.map(String::as_str), writer)
+    }
 }
 
 impl MemoryPackDeserialize for String {
@@ -17,6 +29,12 @@ impl MemoryPackDeserialize for String {
     fn deserialize(reader: &mut MemoryPackReader) -> Result<Self, MemoryPackError> {
         reader.read_string()
     }
+
+    #[cfg(feature = "nightly")]
+    #[inline]
+    fn deserialize_option(reader: &mut MemoryPackReader) -> Result<Option<Self>, MemoryPackError> {
+        deserialize_nullable_string(reader)
+    }
 }
 
 impl MemoryPackSerialize for &str {
@@ -46,3 +64,10 @@ impl MemoryPackDeserialize for Cow<'_, str> {
         Ok(Cow::Owned(reader.read_string()?))
     }
 }
+
+impl<'a> MemoryPackDeserializeZeroCopy<'a> for Cow<'a, str> {
+    #[inline(always)]
+    fn deserialize(reader: &mut MemoryPackReader<'a>) -> Result<Self, MemoryPackError> {
+        reader.read_cow_str()
+    }
+}
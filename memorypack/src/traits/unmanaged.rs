@@ -0,0 +1,136 @@
+use crate::error::MemoryPackError;
+use crate::reader::MemoryPackReader;
+use crate::writer::MemoryPackWriter;
+
+/// Plain-old-data types whose in-memory bytes are their wire format on little-endian
+/// targets, like C# `unmanaged` structs. Collections of them are copied in one block.
+///
+/// Implemented for the numeric primitives, the fixed-layout `glam` types and structs
+/// deriving `MemoryPackable` with `#[memorypack(unmanaged)]`.
+///
+/// # Safety
+/// Implementors must contain no padding, pointers or references, accept every bit
+/// pattern, and serialize as exactly their `size_of::<Self>()` bytes in memory order.
+pub unsafe trait MemoryPackUnmanaged: Copy + 'static {}
+
+macro_rules! impl_unmanaged {
+    ($($ty:ty),*) => {
+        $(unsafe impl MemoryPackUnmanaged for $ty {})*
+    };
+}
+
+impl_unmanaged!(i8, u8, i16, u16, i32, u32, i64, u64, f32, f64, i128, u128);
+
+/// Overrides `MemoryPackSerialize::serialize_slice` with a bulk copy
+macro_rules! unmanaged_serialize {
+    () => {
+        #[cfg(target_endian = "little")]
+        #[inline(always)]
+        fn serialize_slice(items: &[Self], writer: &mut MemoryPackWriter) -> Result<(), MemoryPackError> {
+            $crate::traits::write_unmanaged_slice(items, writer)
+        }
+    };
+}
+
+/// Overrides the `MemoryPackDeserialize` collection hooks with bulk copies
+macro_rules! unmanaged_deserialize {
+    () => {
+        #[cfg(target_endian = "little")]
+        #[inline(always)]
+        fn deserialize_array<const N: usize>(
+            reader: &mut MemoryPackReader,
+        ) -> Result<[Self; N], MemoryPackError> {
+            $crate::traits::read_unmanaged_array(reader)
+        }
+
+        #[cfg(target_endian = "little")]
+        #[inline(always)]
+        fn deserialize_vec(reader: &mut MemoryPackReader, len: usize) -> Result<Vec<Self>, MemoryPackError> {
+            $crate::traits::read_unmanaged_vec(reader, len)
+        }
+    };
+}
+
+pub(crate) use {unmanaged_deserialize, unmanaged_serialize};
+
+#[inline(always)]
+fn as_bytes<T: MemoryPackUnmanaged>(items: &[T]) -> &[u8] {
+    // SAFETY: unmanaged types have no padding, so every byte is initialized
+    unsafe { std::slice::from_raw_parts(items.as_ptr().cast::<u8>(), std::mem::size_of_val(items)) }
+}
+
+#[inline(always)]
+fn as_bytes_mut<T: MemoryPackUnmanaged>(items: &mut [T]) -> &mut [u8] {
+    // SAFETY: unmanaged types accept any bit pattern written into their bytes
+    unsafe {
+        std::slice::from_raw_parts_mut(items.as_mut_ptr().cast::<u8>(), std::mem::size_of_val(items))
+    }
+}
+
+#[doc(hidden)]
+#[inline(always)]
+pub fn write_unmanaged<T: MemoryPackUnmanaged>(
+    value: &T,
+    writer: &mut MemoryPackWriter,
+) -> Result<(), MemoryPackError> {
+    writer.write_bytes(as_bytes(std::slice::from_ref(value)))
+}
+
+#[doc(hidden)]
+#[inline(always)]
+pub fn read_unmanaged<T: MemoryPackUnmanaged>(reader: &mut MemoryPackReader) -> Result<T, MemoryPackError> {
+    // SAFETY: all-zero bytes are a valid unmanaged value
+    let mut value = unsafe { std::mem::MaybeUninit::<T>::zeroed().assume_init() };
+    reader.read_into(as_bytes_mut(std::slice::from_mut(&mut value)))?;
+    Ok(value)
+}
+
+#[doc(hidden)]
+#[inline(always)]
+pub fn write_unmanaged_slice<T: MemoryPackUnmanaged>(
+    items: &[T],
+    writer: &mut MemoryPackWriter,
+) -> Result<(), MemoryPackError> {
+    writer.write_bytes(as_bytes(items))
+}
+
+#[doc(hidden)]
+#[inline(always)]
+pub fn read_unmanaged_array<T: MemoryPackUnmanaged, const N: usize>(
+    reader: &mut MemoryPackReader,
+) -> Result<[T; N], MemoryPackError> {
+    // SAFETY: all-zero bytes are a valid unmanaged value
+    let mut items = unsafe { std::mem::MaybeUninit::<[T; N]>::zeroed().assume_init() };
+    reader.read_into(as_bytes_mut(&mut items))?;
+    Ok(items)
+}
+
+#[doc(hidden)]
+#[inline]
+pub fn read_unmanaged_vec<T: MemoryPackUnmanaged>(
+    reader: &mut MemoryPackReader,
+    len: usize,
+) -> Result<Vec<T>, MemoryPackError> {
+    reader.reserve_elements::<T>(len)?;
+    let byte_len = len
+        .checked_mul(std::mem::size_of::<T>())
+        .ok_or(MemoryPackError::LengthOutOfRange(len as i64))?;
+
+    // Bounds-check against the input before allocating for an untrusted length
+    let bytes = reader.read_transient(byte_len)?;
+    Ok(copy_unmanaged_vec(bytes, len))
+}
+
+/// Copy `len` values out of `bytes`, which must hold exactly `len * size_of::<T>()` bytes
+#[inline]
+pub(crate) fn copy_unmanaged_vec<T: MemoryPackUnmanaged>(bytes: &[u8], len: usize) -> Vec<T> {
+    assert_eq!(bytes.len(), len * std::mem::size_of::<T>());
+
+    let mut items = Vec::<T>::with_capacity(len);
+    // SAFETY: the capacity covers `bytes` and unmanaged types accept any bit pattern
+    unsafe {
+        std::ptr::copy_nonoverlapping(bytes.as_ptr(), items.as_mut_ptr().cast::<u8>(), bytes.len());
+        items.set_len(len);
+    }
+    items
+}
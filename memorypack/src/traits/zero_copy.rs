@@ -0,0 +1,114 @@
+use crate::error::MemoryPackError;
+use crate::reader::MemoryPackReader;
+use crate::traits::{MemoryPackDeserialize, MemoryPackDeserializeZeroCopy};
+
+use std::collections::{BTreeMap, HashMap};
+use std::hash::{BuildHasher, Hash};
+
+// Owned values borrow nothing, so they decode exactly as they do outside zero-copy containers
+macro_rules! impl_zero_copy_owned {
+    ($($ty:ty),*) => {
+        $(
+            impl<'a> MemoryPackDeserializeZeroCopy<'a> for $ty {
+                #[inline(always)]
+                fn deserialize(reader: &mut MemoryPackReader<'a>) -> Result<Self, MemoryPackError> {
+                    <$ty as MemoryPackDeserialize>::deserialize(reader)
+                }
+            }
+        )*
+    };
+}
+
+impl_zero_copy_owned!(
+    bool, i8, u8, i16, u16, i32, u32, i64, u64, f32, f64, i128, u128, char, String
+);
+
+#[inline(always)]
+fn read_length(reader: &mut MemoryPackReader) -> Result<usize, MemoryPackError> {
+    Ok(reader.read_collection_length()?.unwrap_or(0))
+}
+
+impl<'a, T: MemoryPackDeserializeZeroCopy<'a>> MemoryPackDeserializeZeroCopy<'a> for Vec<T> {
+    #[inline]
+    fn deserialize(reader: &mut MemoryPackReader<'a>) -> Result<Self, MemoryPackError> {
+        let len = read_length(reader)?;
+        let mut items = Vec::with_capacity(reader.reserve_elements::<T>(len)?);
+        for i in 0..len {
+            items.push(reader.element(i, T::deserialize)?);
+        }
+        Ok(items)
+    }
+}
+
+/// Same layout as the owned `Option<T>`, decided by `T`
+impl<'a, T: MemoryPackDeserializeZeroCopy<'a>> MemoryPackDeserializeZeroCopy<'a> for Option<T> {
+    #[inline]
+    fn deserialize(reader: &mut MemoryPackReader<'a>) -> Result<Self, MemoryPackError> {
+        T::deserialize_option(reader)
+    }
+}
+
+impl<'a, T: MemoryPackDeserializeZeroCopy<'a>> MemoryPackDeserializeZeroCopy<'a> for Box<T> {
+    #[inline]
+    fn deserialize(reader: &mut MemoryPackReader<'a>) -> Result<Self, MemoryPackError> {
+        T::deserialize(reader).map(Box::new)
+    }
+}
+
+impl<'a, K, V, S> MemoryPackDeserializeZeroCopy<'a> for HashMap<K, V, S>
+where
+    K: MemoryPackDeserializeZeroCopy<'a> + Eq + Hash,
+    V: MemoryPackDeserializeZeroCopy<'a>,
+    S: BuildHasher + Default,
+{
+    #[inline]
+    fn deserialize(reader: &mut MemoryPackReader<'a>) -> Result<Self, MemoryPackError> {
+        let len = read_length(reader)?;
+        let mut map = HashMap::with_capacity_and_hasher(reader.reserve_elements::<(K, V)>(len)?, S::default());
+        for i in 0..len {
+            let (key, value) =
+                reader.element(i, |reader| Ok((K::deserialize(reader)?, V::deserialize(reader)?)))?;
+            map.insert(key, value);
+        }
+        Ok(map)
+    }
+}
+
+impl<'a, K, V> MemoryPackDeserializeZeroCopy<'a> for BTreeMap<K, V>
+where
+    K: MemoryPackDeserializeZeroCopy<'a> + Ord,
+    V: MemoryPackDeserializeZeroCopy<'a>,
+{
+    #[inline]
+    fn deserialize(reader: &mut MemoryPackReader<'a>) -> Result<Self, MemoryPackError> {
+        let len = read_length(reader)?;
+        reader.reserve_elements::<(K, V)>(len)?;
+        let mut map = BTreeMap::new();
+        for i in 0..len {
+            let (key, value) =
+                reader.element(i, |reader| Ok((K::deserialize(reader)?, V::deserialize(reader)?)))?;
+            map.insert(key, value);
+        }
+        Ok(map)
+    }
+}
+
+#[cfg(feature = "hashbrown")]
+impl<'a, K, V, S> MemoryPackDeserializeZeroCopy<'a> for hashbrown::HashMap<K, V, S>
+where
+    K: MemoryPackDeserializeZeroCopy<'a> + Eq + Hash,
+    V: MemoryPackDeserializeZeroCopy<'a>,
+    S: BuildHasher + Default,
+{
+    #[inline]
+    fn deserialize(reader: &mut MemoryPackReader<'a>) -> Result<Self, MemoryPackError> {
+        let len = read_length(reader)?;
+        let mut map = hashbrown::HashMap::with_capacity_and_hasher(reader.reserve_elements::<(K, V)>(len)?, S::default());
+        for i in 0..len {
+            let (key, value) =
+                reader.element(i, |reader| Ok((K::deserialize(reader)?, V::deserialize(reader)?)))?;
+            map.insert(key, value);
+        }
+        Ok(map)
+    }
+}
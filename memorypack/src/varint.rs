@@ -2,6 +2,9 @@ use crate::error::MemoryPackError;
 use crate::reader::MemoryPackReader;
 use crate::writer::MemoryPackWriter;
 
+/// Largest number of bytes a single varint can occupy (type code + 8 byte payload)
+pub const MAX_VARINT_LENGTH: usize = 9;
+
 mod codes {
     pub const MAX_SINGLE_VALUE: i8 = 127;
     pub const MIN_SINGLE_VALUE: i8 = -120;
@@ -16,36 +19,46 @@ mod codes {
     pub const INT64: i8 = -128;
 }
 
-pub fn write_varint(writer: &mut MemoryPackWriter, value: i64) -> Result<(), MemoryPackError> {
+/// Encode a varint into `buffer`, returning the number of bytes used.
+///
+/// `buffer` must hold at least [`MAX_VARINT_LENGTH`] bytes.
+pub fn encode_varint(value: i64, buffer: &mut [u8]) -> usize {
+    #[inline(always)]
+    fn put(buffer: &mut [u8], code: i8, payload: &[u8]) -> usize {
+        buffer[0] = code as u8;
+        buffer[1..=payload.len()].copy_from_slice(payload);
+        payload.len() + 1
+    }
+
     if value >= 0 {
         if value <= codes::MAX_SINGLE_VALUE as i64 {
-            writer.write_i8(value as i8)?;
+            buffer[0] = value as u8;
+            1
         } else if value <= i16::MAX as i64 {
-            writer.write_i8(codes::INT16)?;
-            writer.write_i16(value as i16)?;
+            put(buffer, codes::INT16, &(value as i16).to_le_bytes())
         } else if value <= i32::MAX as i64 {
-            writer.write_i8(codes::INT32)?;
-            writer.write_i32(value as i32)?;
+            put(buffer, codes::INT32, &(value as i32).to_le_bytes())
         } else {
-            writer.write_i8(codes::INT64)?;
-            writer.write_i64(value)?;
+            put(buffer, codes::INT64, &value.to_le_bytes())
         }
     } else if value >= codes::MIN_SINGLE_VALUE as i64 {
-        writer.write_i8(value as i8)?;
+        buffer[0] = value as u8;
+        1
     } else if value >= i8::MIN as i64 {
-        writer.write_i8(codes::SBYTE)?;
-        writer.write_i8(value as i8)?;
+        put(buffer, codes::SBYTE, &(value as i8).to_le_bytes())
     } else if value >= i16::MIN as i64 {
-        writer.write_i8(codes::INT16)?;
-        writer.write_i16(value as i16)?;
+        put(buffer, codes::INT16, &(value as i16).to_le_bytes())
     } else if value >= i32::MIN as i64 {
-        writer.write_i8(codes::INT32)?;
-        writer.write_i32(value as i32)?;
+        put(buffer, codes::INT32, &(value as i32).to_le_bytes())
     } else {
-        writer.write_i8(codes::INT64)?;
-        writer.write_i64(value)?;
+        put(buffer, codes::INT64, &value.to_le_bytes())
     }
-    Ok(())
+}
+
+pub fn write_varint(writer: &mut MemoryPackWriter, value: i64) -> Result<(), MemoryPackError> {
+    let mut buffer = [0u8; MAX_VARINT_LENGTH];
+    let len = encode_varint(value, &mut buffer);
+    writer.write_bytes(&buffer[..len])
 }
 
 pub fn read_varint(reader: &mut MemoryPackReader) -> Result<i64, MemoryPackError> {
@@ -63,3 +76,9 @@ pub fn read_varint(reader: &mut MemoryPackReader) -> Result<i64, MemoryPackError
         _ => Ok(type_code as i64),
     }
 }
+
+/// Read a varint member length, rejecting negative values
+pub fn read_varint_length(reader: &mut MemoryPackReader) -> Result<usize, MemoryPackError> {
+    let length = read_varint(reader)?;
+    usize::try_from(length).map_err(|_| MemoryPackError::LengthOutOfRange(length))
+}
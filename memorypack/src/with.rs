@@ -0,0 +1,44 @@
+//! Field formatters for `#[memorypack(with = "...")]`
+//!
+//! Each module provides `serialize(&T, &mut MemoryPackWriter)` and
+//! `deserialize(&mut MemoryPackReader) -> T`, usable as a whole with `with`
+//! or one at a time with `serialize_with` / `deserialize_with`
+
+/// `std::time::Duration` as a C# `TimeSpan` (an `i64` count of 100ns ticks)
+pub mod timespan {
+    use crate::error::MemoryPackError;
+    use crate::reader::MemoryPackReader;
+    use crate::writer::MemoryPackWriter;
+    use std::time::Duration;
+
+    const NANOS_PER_TICK: u128 = 100;
+
+    pub fn serialize(value: &Duration, writer: &mut MemoryPackWriter) -> Result<(), MemoryPackError> {
+        let ticks = i64::try_from(value.as_nanos() / NANOS_PER_TICK)
+            .map_err(|_| MemoryPackError::SerializationError("Duration out of TimeSpan range".into()))?;
+        writer.write_i64(ticks)
+    }
+
+    pub fn deserialize(reader: &mut MemoryPackReader) -> Result<Duration, MemoryPackError> {
+        let ticks = reader.read_i64()?;
+        let ticks = u64::try_from(ticks).map_err(|_| {
+            MemoryPackError::DeserializationError(format!("Negative TimeSpan {} ticks", ticks))
+        })?;
+        Ok(Duration::new(ticks / 10_000_000, (ticks % 10_000_000) as u32 * 100))
+    }
+}
+
+/// `String` as UTF-16 code units instead of the default UTF-8 layout
+pub mod utf16 {
+    use crate::error::MemoryPackError;
+    use crate::reader::MemoryPackReader;
+    use crate::writer::MemoryPackWriter;
+
+    pub fn serialize(value: &str, writer: &mut MemoryPackWriter) -> Result<(), MemoryPackError> {
+        writer.write_utf16_string(value)
+    }
+
+    pub fn deserialize(reader: &mut MemoryPackReader) -> Result<String, MemoryPackError> {
+        reader.read_string()
+    }
+}
@@ -1,46 +1,274 @@
 use crate::error::MemoryPackError;
+use crate::options::{MemoryPackSerializerOptions, StringEncoding};
 use crate::state::MemoryPackWriterOptionalState;
 use crate::varint;
 
-pub struct MemoryPackWriter {
-    pub buffer: Vec<u8>,
+use std::io::Write;
+
+const DEFAULT_STREAM_BUFFER_SIZE: usize = 8 * 1024;
+
+enum Output<'a> {
+    Buffer,
+    Stream(&'a mut dyn Write),
+    Slice { target: &'a mut [u8], written: usize },
+    Count { written: usize },
+}
+
+pub struct MemoryPackWriter<'a> {
+    buffer: Vec<u8>,
+    output: Output<'a>,
+    flushed: usize,
+    flush_threshold: usize,
+    pinned: Option<usize>,
+    open_reservations: usize,
+    options: MemoryPackSerializerOptions,
     pub optional_state: Option<MemoryPackWriterOptionalState>,
 }
 
-impl MemoryPackWriter {
+/// A region of the output reserved with [`MemoryPackWriter::reserve`].
+///
+/// Bytes written after the reservation stay buffered until it is committed,
+/// so the region can still be patched on streaming writers.
+#[must_use]
+#[derive(Debug)]
+pub struct Reservation {
+    offset: usize,
+    len: usize,
+}
+
+impl<'a> MemoryPackWriter<'a> {
     pub fn new() -> Self {
-        Self {
-            buffer: Vec::new(),
-            optional_state: None,
-        }
+        Self::with_capacity(0)
     }
 
     pub fn new_with_state() -> Self {
         Self {
-            buffer: Vec::new(),
             optional_state: Some(MemoryPackWriterOptionalState::new()),
+            ..Self::new()
         }
     }
 
     pub fn with_capacity(capacity: usize) -> Self {
+        Self::with_output(Vec::with_capacity(capacity), Output::Buffer, usize::MAX)
+    }
+
+    /// Create a writer that flushes to `sink` whenever its internal buffer fills up
+    pub fn from_stream<W: Write>(sink: &'a mut W) -> Self {
+        Self::from_stream_with_capacity(sink, DEFAULT_STREAM_BUFFER_SIZE)
+    }
+
+    /// Create a streaming writer that buffers up to `capacity` bytes before flushing
+    pub fn from_stream_with_capacity<W: Write>(sink: &'a mut W, capacity: usize) -> Self {
+        Self::with_output(
+            Vec::with_capacity(capacity),
+            Output::Stream(sink),
+            capacity.max(1),
+        )
+    }
+
+    /// Create a writer that fills `target` in place and never allocates an output buffer.
+    ///
+    /// Writes that do not fit fail with [`MemoryPackError::BufferTooSmall`]; [`len`](Self::len)
+    /// reports how many bytes of `target` were used.
+    pub fn from_slice(target: &'a mut [u8]) -> Self {
+        Self::with_output(Vec::new(), Output::Slice { target, written: 0 }, usize::MAX)
+    }
+
+    /// Create a writer that only counts the bytes it is given, for sizing payloads up front;
+    /// `options` must match the ones the payload is written with, since they change its size
+    pub fn counting(options: MemoryPackSerializerOptions) -> Self {
+        Self::with_output(Vec::new(), Output::Count { written: 0 }, usize::MAX).with_options(options)
+    }
+
+    fn with_output(buffer: Vec<u8>, output: Output<'a>, flush_threshold: usize) -> Self {
         Self {
-            buffer: Vec::with_capacity(capacity),
+            buffer,
+            output,
+            flushed: 0,
+            flush_threshold,
+            pinned: None,
+            open_reservations: 0,
+            options: MemoryPackSerializerOptions::default(),
             optional_state: None,
         }
     }
 
+    /// Apply `options` to everything written from now on
+    pub fn with_options(mut self, options: MemoryPackSerializerOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    #[inline]
+    pub fn options(&self) -> &MemoryPackSerializerOptions {
+        &self.options
+    }
+
+    #[inline]
+    pub fn set_options(&mut self, options: MemoryPackSerializerOptions) {
+        self.options = options;
+    }
+
+    /// Number of bytes written so far, including bytes already flushed to the sink
     #[inline]
     pub fn len(&self) -> usize {
-        self.buffer.len()
+        self.position()
     }
 
     #[inline]
     pub fn is_empty(&self) -> bool {
-        self.buffer.is_empty()
+        self.position() == 0
+    }
+
+    #[inline]
+    pub fn position(&self) -> usize {
+        match &self.output {
+            Output::Slice { written, .. } | Output::Count { written } => *written,
+            _ => self.flushed + self.buffer.len(),
+        }
+    }
+
+    #[inline(always)]
+    pub fn write_bytes(&mut self, bytes: &[u8]) -> Result<(), MemoryPackError> {
+        if let Output::Count { written } = &mut self.output {
+            *written += bytes.len();
+            return Ok(());
+        }
+        if let Output::Slice { target, written } = &mut self.output {
+            let end = *written + bytes.len();
+            target
+                .get_mut(*written..end)
+                .ok_or(MemoryPackError::BufferTooSmall)?
+                .copy_from_slice(bytes);
+            *written = end;
+            return Ok(());
+        }
+
+        self.buffer.extend_from_slice(bytes);
+        if self.buffer.len() >= self.flush_threshold {
+            self.flush_buffered()?;
+        }
+        Ok(())
+    }
+
+    /// Reserve `len` zeroed bytes at the current position to be filled in later with
+    /// [`commit`](Self::commit). Reservations must be committed in reverse order.
+    #[inline]
+    pub fn reserve(&mut self, len: usize) -> Result<Reservation, MemoryPackError> {
+        let offset = self.position();
+
+        if let Output::Count { written } = &mut self.output {
+            *written += len;
+        } else if let Output::Slice { target, written } = &mut self.output {
+            let end = *written + len;
+            target
+                .get_mut(*written..end)
+                .ok_or(MemoryPackError::BufferTooSmall)?
+                .fill(0);
+            *written = end;
+        } else {
+            self.pinned.get_or_insert(offset);
+            self.buffer.resize(self.buffer.len() + len, 0);
+        }
+
+        self.open_reservations += 1;
+        Ok(Reservation { offset, len })
     }
 
+    /// Replace a reserved region with `bytes`, which may be longer or shorter than the
+    /// reservation; everything written after it is shifted accordingly.
+    #[inline]
+    pub fn commit(&mut self, reservation: Reservation, bytes: &[u8]) -> Result<(), MemoryPackError> {
+        if let Output::Count { written } = &mut self.output {
+            *written = *written - reservation.len + bytes.len();
+            self.open_reservations -= 1;
+            return Ok(());
+        }
+        if let Output::Slice { target, written } = &mut self.output {
+            let start = reservation.offset;
+            let end = start + reservation.len;
+            let new_end = start + bytes.len();
+            let new_written = *written - reservation.len + bytes.len();
+            if new_written > target.len() {
+                return Err(MemoryPackError::BufferTooSmall);
+            }
+
+            target.copy_within(end..*written, new_end);
+            target[start..new_end].copy_from_slice(bytes);
+            *written = new_written;
+            self.open_reservations -= 1;
+            return Ok(());
+        }
+
+        let start = reservation
+            .offset
+            .checked_sub(self.flushed)
+            .filter(|&start| start + reservation.len <= self.buffer.len())
+            .ok_or_else(|| {
+                MemoryPackError::SerializationError("Reservation is no longer buffered".into())
+            })?;
+        let end = start + reservation.len;
+
+        if bytes.len() == reservation.len {
+            self.buffer[start..end].copy_from_slice(bytes);
+        } else {
+            self.buffer.splice(start..end, bytes.iter().copied());
+        }
+
+        self.open_reservations -= 1;
+        if self.open_reservations == 0 {
+            self.pinned = None;
+            if self.buffer.len() >= self.flush_threshold {
+                self.flush_buffered()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Write all buffered bytes to the sink and flush it.
+    ///
+    /// Bytes behind an uncommitted [`Reservation`] are kept back. Does nothing for
+    /// in-memory writers.
+    pub fn flush(&mut self) -> Result<(), MemoryPackError> {
+        self.flush_buffered()?;
+        if let Output::Stream(sink) = &mut self.output {
+            sink.flush()?;
+        }
+        Ok(())
+    }
+
+    #[cold]
+    fn flush_buffered(&mut self) -> Result<(), MemoryPackError> {
+        let Output::Stream(sink) = &mut self.output else {
+            return Ok(());
+        };
+
+        let flushable = match self.pinned {
+            Some(offset) => offset - self.flushed,
+            None => self.buffer.len(),
+        };
+        if flushable == 0 {
+            return Ok(());
+        }
+
+        sink.write_all(&self.buffer[..flushable])?;
+        self.buffer.drain(..flushable);
+        self.flushed += flushable;
+        Ok(())
+    }
+
+    /// Write `value` in the string encoding selected by the writer's options
     #[inline]
     pub fn write_string(&mut self, value: &str) -> Result<(), MemoryPackError> {
+        match self.options.string_encoding {
+            StringEncoding::Utf8 => self.write_utf8_string(value),
+            StringEncoding::Utf16 => self.write_utf16_string(value),
+        }
+    }
+
+    /// Write `value` as UTF-8 bytes, the layout C# uses with `StringEncoding.Utf8`
+    #[inline]
+    pub fn write_utf8_string(&mut self, value: &str) -> Result<(), MemoryPackError> {
         if value.is_empty() {
             return self.write_i32(0);
         }
@@ -48,7 +276,17 @@ impl MemoryPackWriter {
         let utf16_length: usize = value.chars().map(|c| c.len_utf16()).sum();
         self.write_i32(!(bytes.len() as i32))?;
         self.write_i32(utf16_length as i32)?;
-        self.buffer.extend_from_slice(bytes);
+        self.write_bytes(bytes)
+    }
+
+    /// Write `value` as UTF-16 code units, the C# MemoryPack default layout
+    #[inline]
+    pub fn write_utf16_string(&mut self, value: &str) -> Result<(), MemoryPackError> {
+        let utf16_length: usize = value.chars().map(|c| c.len_utf16()).sum();
+        self.write_i32(utf16_length as i32)?;
+        for unit in value.encode_utf16() {
+            self.write_u16(unit)?;
+        }
         Ok(())
     }
 
@@ -62,113 +300,106 @@ impl MemoryPackWriter {
 
     #[inline(always)]
     pub fn write_bool(&mut self, value: bool) -> Result<(), MemoryPackError> {
-        self.buffer.push(value as u8);
-        Ok(())
+        self.write_bytes(&[value as u8])
     }
 
     #[inline(always)]
     pub fn write_i8(&mut self, value: i8) -> Result<(), MemoryPackError> {
-        self.buffer.push(value as u8);
-        Ok(())
+        self.write_bytes(&[value as u8])
     }
 
     #[inline(always)]
     pub fn write_u8(&mut self, value: u8) -> Result<(), MemoryPackError> {
-        self.buffer.push(value);
-        Ok(())
+        self.write_bytes(&[value])
     }
 
     #[inline(always)]
     pub fn write_i16(&mut self, value: i16) -> Result<(), MemoryPackError> {
-        self.buffer.extend_from_slice(&value.to_le_bytes());
-        Ok(())
+        self.write_bytes(&value.to_le_bytes())
     }
 
     #[inline(always)]
     pub fn write_u16(&mut self, value: u16) -> Result<(), MemoryPackError> {
-        self.buffer.extend_from_slice(&value.to_le_bytes());
-        Ok(())
+        self.write_bytes(&value.to_le_bytes())
     }
 
     #[inline(always)]
     pub fn write_i32(&mut self, value: i32) -> Result<(), MemoryPackError> {
-        self.buffer.extend_from_slice(&value.to_le_bytes());
-        Ok(())
+        self.write_bytes(&value.to_le_bytes())
     }
 
     #[inline(always)]
     pub fn write_u32(&mut self, value: u32) -> Result<(), MemoryPackError> {
-        self.buffer.extend_from_slice(&value.to_le_bytes());
-        Ok(())
+        self.write_bytes(&value.to_le_bytes())
     }
 
     #[inline(always)]
     pub fn write_i64(&mut self, value: i64) -> Result<(), MemoryPackError> {
-        self.buffer.extend_from_slice(&value.to_le_bytes());
-        Ok(())
+        self.write_bytes(&value.to_le_bytes())
     }
 
     #[inline(always)]
     pub fn write_u64(&mut self, value: u64) -> Result<(), MemoryPackError> {
-        self.buffer.extend_from_slice(&value.to_le_bytes());
-        Ok(())
+        self.write_bytes(&value.to_le_bytes())
     }
 
     #[inline(always)]
     pub fn write_f32(&mut self, value: f32) -> Result<(), MemoryPackError> {
-        self.buffer.extend_from_slice(&value.to_le_bytes());
-        Ok(())
+        self.write_bytes(&value.to_le_bytes())
     }
 
     #[inline(always)]
     pub fn write_f64(&mut self, value: f64) -> Result<(), MemoryPackError> {
-        self.buffer.extend_from_slice(&value.to_le_bytes());
-        Ok(())
+        self.write_bytes(&value.to_le_bytes())
     }
 
     #[inline(always)]
     pub fn write_i128(&mut self, value: i128) -> Result<(), MemoryPackError> {
-        self.buffer.extend_from_slice(&value.to_le_bytes());
-        Ok(())
+        self.write_bytes(&value.to_le_bytes())
     }
 
     #[inline(always)]
     pub fn write_u128(&mut self, value: u128) -> Result<(), MemoryPackError> {
-        self.buffer.extend_from_slice(&value.to_le_bytes());
-        Ok(())
+        self.write_bytes(&value.to_le_bytes())
     }
 
     #[inline(always)]
     pub fn write_char(&mut self, value: char) -> Result<(), MemoryPackError> {
         let code = value as u32;
         if code <= 0xFFFF {
-            self.buffer.extend_from_slice(&(code as u16).to_le_bytes());
+            self.write_bytes(&(code as u16).to_le_bytes())
         } else {
             let adjusted = code - 0x10000;
             let high_surrogate = ((adjusted >> 10) as u16) + 0xD800;
-            self.buffer.extend_from_slice(&high_surrogate.to_le_bytes());
+            self.write_bytes(&high_surrogate.to_le_bytes())
         }
-        Ok(())
     }
 
+    /// Bytes that have not been flushed yet; for in-memory writers this is the whole output
     #[inline]
     pub fn into_bytes(self) -> Vec<u8> {
-        self.buffer
+        match self.output {
+            Output::Slice { target, written } => target[..written].to_vec(),
+            _ => self.buffer,
+        }
     }
 
     #[inline]
     pub fn as_bytes(&self) -> &[u8] {
-        &self.buffer
+        match &self.output {
+            Output::Slice { target, written } => &target[..*written],
+            _ => &self.buffer,
+        }
     }
 }
 
-impl Default for MemoryPackWriter {
+impl Default for MemoryPackWriter<'_> {
     fn default() -> Self {
         Self::new()
     }
 }
 
-impl MemoryPackWriter {
+impl MemoryPackWriter<'_> {
     pub fn write_object_reference_id(&mut self, reference_id: u32) -> Result<(), MemoryPackError> {
         self.write_u8(250)?;
         varint::write_varint(self, reference_id as i64)?;
@@ -0,0 +1,171 @@
+use std::time::Duration;
+
+use futures::{SinkExt, StreamExt};
+use memorypack::prelude::*;
+use memorypack::{MemoryPackCodec, MemoryPackReaderLimits};
+use tokio::io::{AsyncWriteExt, duplex};
+use tokio::time::timeout;
+use tokio_util::codec::{Framed, FramedRead};
+
+#[derive(MemoryPackable, Debug, Clone, PartialEq, Default)]
+struct Message {
+    id: u32,
+    body: String,
+    payload: Vec<u8>,
+    reply_to: Option<u32>,
+}
+
+fn message(id: u32, size: usize) -> Message {
+    Message {
+        id,
+        body: format!("message {id}"),
+        payload: vec![id as u8; size],
+        reply_to: id.checked_sub(1),
+    }
+}
+
+/// Fail the test instead of hanging when a read never completes
+const DEADLINE: Duration = Duration::from_secs(5);
+
+#[tokio::test]
+async fn framed_functions_round_trip() {
+    let (mut client, mut server) = duplex(64);
+
+    let writer = tokio::spawn(async move {
+        for id in 0..3 {
+            MemoryPackSerializer::serialize_to_async(&message(id, 1_000), &mut client).await.unwrap();
+        }
+    });
+
+    for id in 0..3 {
+        let received: Message = timeout(DEADLINE, MemoryPackSerializer::deserialize_from_async(&mut server))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(received, message(id, 1_000));
+    }
+    writer.await.unwrap();
+}
+
+#[tokio::test]
+async fn framed_functions_reject_large_frame() {
+    let (mut client, mut server) = duplex(4_096);
+    MemoryPackSerializer::serialize_to_async(&message(1, 200), &mut client).await.unwrap();
+
+    let result = MemoryPackSerializer::deserialize_from_async_with_limits::<Message, _>(
+        &mut server,
+        100,
+        MemoryPackReaderLimits::default(),
+    )
+    .await;
+    assert!(matches!(result, Err(MemoryPackError::FrameTooLarge(length, 100)) if length > 200));
+
+    let (mut client, mut server) = duplex(64);
+    client.write_all(&i32::MAX.to_le_bytes()).await.unwrap();
+    let result = timeout(DEADLINE, MemoryPackSerializer::deserialize_from_async::<Message, _>(&mut server))
+        .await
+        .unwrap();
+    assert!(matches!(result, Err(MemoryPackError::FrameTooLarge(..))));
+}
+
+#[tokio::test]
+async fn framed_functions_error_on_truncated_frame() {
+    let (mut client, mut server) = duplex(4_096);
+    let mut frame = Vec::new();
+    MemoryPackSerializer::serialize_to_async(&message(2, 50), &mut frame).await.unwrap();
+
+    client.write_all(&frame[..frame.len() - 10]).await.unwrap();
+    drop(client);
+
+    let result = timeout(DEADLINE, MemoryPackSerializer::deserialize_from_async::<Message, _>(&mut server))
+        .await
+        .unwrap();
+    assert!(matches!(result, Err(MemoryPackError::UnexpectedEnd)));
+
+    let (mut client, mut server) = duplex(64);
+    client.write_all(&[8, 0]).await.unwrap();
+    drop(client);
+
+    let result = timeout(DEADLINE, MemoryPackSerializer::deserialize_from_async::<Message, _>(&mut server))
+        .await
+        .unwrap();
+    assert!(matches!(result, Err(MemoryPackError::UnexpectedEnd)));
+}
+
+#[tokio::test]
+async fn framed_functions_reject_negative_length() {
+    let (mut client, mut server) = duplex(64);
+    client.write_all(&(-4i32).to_le_bytes()).await.unwrap();
+
+    let result = MemoryPackSerializer::deserialize_from_async::<Message, _>(&mut server).await;
+    assert!(matches!(result, Err(MemoryPackError::InvalidLength(-4))));
+}
+
+#[tokio::test]
+async fn codec_round_trip() {
+    let (client, server) = duplex(64);
+    let mut client = Framed::new(client, MemoryPackCodec::<Message>::new());
+    let mut server = Framed::new(server, MemoryPackCodec::<Message>::new());
+
+    let writer = tokio::spawn(async move {
+        for id in 0..5 {
+            client.send(message(id, 300)).await.unwrap();
+        }
+        client
+    });
+
+    for id in 0..5 {
+        let received = timeout(DEADLINE, server.next()).await.unwrap().unwrap().unwrap();
+        assert_eq!(received, message(id, 300));
+    }
+
+    // Closing the client ends the stream cleanly
+    drop(writer.await.unwrap());
+    assert!(timeout(DEADLINE, server.next()).await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn codec_matches_framed_functions() {
+    let (client, mut server) = duplex(4_096);
+    let mut client = Framed::new(client, MemoryPackCodec::<Message>::new());
+    client.send(message(3, 10)).await.unwrap();
+
+    let received: Message = MemoryPackSerializer::deserialize_from_async(&mut server).await.unwrap();
+    assert_eq!(received, message(3, 10));
+
+    let (mut client, server) = duplex(4_096);
+    let mut server = FramedRead::new(server, MemoryPackCodec::<Message>::new());
+    MemoryPackSerializer::serialize_to_async(&message(4, 10), &mut client).await.unwrap();
+
+    let received = timeout(DEADLINE, server.next()).await.unwrap().unwrap().unwrap();
+    assert_eq!(received, message(4, 10));
+}
+
+#[tokio::test]
+async fn codec_rejects_large_frame() {
+    let (client, server) = duplex(4_096);
+    let mut client = Framed::new(client, MemoryPackCodec::<Message>::new());
+    let mut server = Framed::new(server, MemoryPackCodec::<Message>::with_max_frame_length(100));
+
+    client.send(message(1, 200)).await.unwrap();
+    let result = timeout(DEADLINE, server.next()).await.unwrap().unwrap();
+    assert!(matches!(result, Err(MemoryPackError::FrameTooLarge(length, 100)) if length > 200));
+
+    let mut client = Framed::new(duplex(64).0, MemoryPackCodec::<Message>::with_max_frame_length(100));
+    let result = client.send(message(1, 200)).await;
+    assert!(matches!(result, Err(MemoryPackError::FrameTooLarge(_, 100))));
+}
+
+#[tokio::test]
+async fn codec_errors_on_truncated_frame() {
+    let (mut client, server) = duplex(4_096);
+    let mut server = FramedRead::new(server, MemoryPackCodec::<Message>::new());
+
+    let mut frame = Vec::new();
+    MemoryPackSerializer::serialize_to_async(&message(2, 50), &mut frame).await.unwrap();
+    client.write_all(&frame[..frame.len() - 10]).await.unwrap();
+    drop(client);
+
+    let result = timeout(DEADLINE, server.next()).await.unwrap().unwrap();
+    assert!(result.is_err());
+}
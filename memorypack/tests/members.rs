@@ -0,0 +1,86 @@
+use memorypack::prelude::*;
+
+#[derive(MemoryPackable, Debug, Clone, PartialEq)]
+struct UserV1 {
+    id: u32,
+    name: String,
+}
+
+#[derive(MemoryPackable, Debug, Clone, PartialEq)]
+struct UserV2 {
+    id: u32,
+    name: String,
+    email: Option<String>,
+    roles: Vec<String>,
+    #[memorypack(default = "30")]
+    timeout: u16,
+}
+
+#[derive(MemoryPackable, Debug, Clone, PartialEq)]
+struct PointV1(i32, i32);
+
+#[derive(MemoryPackable, Debug, Clone, PartialEq)]
+struct PointV2(i32, i32, i32);
+
+#[derive(MemoryPackable, Debug, Clone, PartialEq)]
+struct Wrapper<T: MemoryPackSerialize + MemoryPackDeserialize + MemoryPackSchema + Default> {
+    id: u32,
+    value: T,
+}
+
+#[derive(MemoryPackable, Debug, Clone, PartialEq)]
+enum Shape {
+    #[memorypack(tag = 0)]
+    Circle(f32),
+}
+
+#[derive(MemoryPackable, Debug, Clone, PartialEq)]
+struct WithShape {
+    id: u32,
+    name: String,
+    shape: Shape,
+}
+
+#[test]
+fn missing_members_get_defaults() {
+    let old = UserV1 { id: 9, name: "ada".into() };
+    let bytes = MemoryPackSerializer::serialize(&old).unwrap();
+
+    let new: UserV2 = MemoryPackSerializer::deserialize(&bytes).unwrap();
+    assert_eq!(
+        new,
+        UserV2 {
+            id: 9,
+            name: "ada".into(),
+            email: None,
+            roles: vec![],
+            timeout: 30,
+        }
+    );
+
+    let bytes = MemoryPackSerializer::serialize(&PointV1(1, 2)).unwrap();
+    let point: PointV2 = MemoryPackSerializer::deserialize(&bytes).unwrap();
+    assert_eq!(point, PointV2(1, 2, 0));
+}
+
+#[test]
+fn missing_generic_member() {
+    let mut bytes = vec![1];
+    bytes.extend_from_slice(&7u32.to_le_bytes());
+    let wrapper: Wrapper<String> = MemoryPackSerializer::deserialize(&bytes).unwrap();
+    assert_eq!(wrapper, Wrapper { id: 7, value: String::new() });
+}
+
+#[test]
+fn missing_member_without_default_errors() {
+    let bytes = MemoryPackSerializer::serialize(&UserV1 { id: 1, name: "x".into() }).unwrap();
+    let error = MemoryPackSerializer::deserialize::<WithShape>(&bytes).unwrap_err();
+    assert!(matches!(error.kind(), MemoryPackError::MemberCountMismatch("WithShape", 3, 2)), "{error:?}");
+}
+
+#[test]
+fn extra_members_error() {
+    let bytes = MemoryPackSerializer::serialize(&PointV2(1, 2, 3)).unwrap();
+    let error = MemoryPackSerializer::deserialize::<PointV1>(&bytes).unwrap_err();
+    assert!(matches!(error.kind(), MemoryPackError::MemberCountMismatch("PointV1", 2, 3)), "{error:?}");
+}
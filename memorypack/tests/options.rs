@@ -0,0 +1,86 @@
+use memorypack::prelude::*;
+use memorypack::NullableObject;
+
+/// Hand-written value type, like C# `Nullable<Meters>`
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+struct Meters(f64);
+
+impl MemoryPackSerialize for Meters {
+    fn serialize(&self, writer: &mut MemoryPackWriter) -> Result<(), MemoryPackError> {
+        writer.write_f64(self.0)
+    }
+}
+
+impl MemoryPackDeserialize for Meters {
+    fn deserialize(reader: &mut MemoryPackReader) -> Result<Self, MemoryPackError> {
+        Ok(Meters(reader.read_f64()?))
+    }
+}
+
+#[derive(MemoryPackable, Debug, Clone, PartialEq)]
+enum Shape {
+    #[memorypack(tag = 0)]
+    Circle(f32),
+    #[memorypack(tag = 1)]
+    Square { side: f32 },
+}
+
+#[derive(MemoryPackable, Debug, Clone, PartialEq, Default)]
+struct Point {
+    x: i32,
+    y: i32,
+}
+
+#[derive(MemoryPackable, Debug, Clone, PartialEq)]
+struct Drawing {
+    origin: Option<Point>,
+    #[memorypack(nullable_object)]
+    shape: Option<Shape>,
+    outline: NullableObject<Shape>,
+}
+
+#[test]
+fn hand_written_default_type() {
+    let none = MemoryPackSerializer::serialize(&None::<Meters>).unwrap();
+    assert_eq!(none, [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+    assert_eq!(MemoryPackSerializer::deserialize::<Option<Meters>>(&none).unwrap(), None);
+
+    let some = MemoryPackSerializer::serialize(&Some(Meters(2.5))).unwrap();
+    assert_eq!(some[..4], [1, 0, 0, 0]);
+    assert_eq!(MemoryPackSerializer::deserialize::<Option<Meters>>(&some).unwrap(), Some(Meters(2.5)));
+
+    let boxed = MemoryPackSerializer::serialize(&None::<Box<Meters>>).unwrap();
+    assert_eq!(boxed, none);
+}
+
+#[test]
+fn derived_objects_use_null_marker() {
+    let none = MemoryPackSerializer::serialize(&None::<Point>).unwrap();
+    assert_eq!(none, [255]);
+    assert_eq!(MemoryPackSerializer::deserialize::<Option<Point>>(&none).unwrap(), None);
+
+    let boxed = MemoryPackSerializer::serialize(&None::<Box<Point>>).unwrap();
+    assert_eq!(boxed, [255]);
+}
+
+#[test]
+fn null_object_modes_need_no_default() {
+    let drawing = Drawing {
+        origin: Some(Point { x: 1, y: 2 }),
+        shape: None,
+        outline: NullableObject(Some(Shape::Square { side: 3.0 })),
+    };
+    let bytes = MemoryPackSerializer::serialize(&drawing).unwrap();
+    assert_eq!(MemoryPackSerializer::deserialize::<Drawing>(&bytes).unwrap(), drawing);
+
+    let none = MemoryPackSerializer::serialize(&NullableObject::<Shape>(None)).unwrap();
+    assert_eq!(none, [255]);
+
+    let some = Drawing {
+        origin: None,
+        shape: Some(Shape::Circle(0.5)),
+        outline: NullableObject(None),
+    };
+    let bytes = MemoryPackSerializer::serialize(&some).unwrap();
+    assert_eq!(MemoryPackSerializer::deserialize::<Drawing>(&bytes).unwrap(), some);
+}
@@ -0,0 +1,70 @@
+use memorypack::prelude::*;
+use memorypack::schema::{Primitive, Schema};
+
+/// Hand-written type with no `MemoryPackSchema` impl
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+struct Meters(f64);
+
+impl MemoryPackSerialize for Meters {
+    fn serialize(&self, writer: &mut MemoryPackWriter) -> Result<(), MemoryPackError> {
+        writer.write_f64(self.0)
+    }
+}
+
+impl MemoryPackDeserialize for Meters {
+    fn deserialize(reader: &mut MemoryPackReader) -> Result<Self, MemoryPackError> {
+        Ok(Meters(reader.read_f64()?))
+    }
+}
+
+#[derive(MemoryPackable, Debug, Clone, PartialEq)]
+struct Route {
+    id: u32,
+    length: Meters,
+    detour: Option<Meters>,
+    #[memorypack(schema = "Distance")]
+    climb: Meters,
+}
+
+#[derive(MemoryPackable, Debug, Clone, PartialEq)]
+struct Leg(Meters, Vec<Meters>);
+
+fn members(schema: Schema) -> Vec<Schema> {
+    match schema {
+        Schema::Object(object) => object.members.into_iter().map(|member| member.schema).collect(),
+        other => panic!("expected an object, found {other:?}"),
+    }
+}
+
+#[test]
+fn hand_written_fields_compile_without_schema() {
+    let route = Route {
+        id: 4,
+        length: Meters(12.5),
+        detour: Some(Meters(0.5)),
+        climb: Meters(3.0),
+    };
+    let bytes = MemoryPackSerializer::serialize(&route).unwrap();
+    assert_eq!(MemoryPackSerializer::deserialize::<Route>(&bytes).unwrap(), route);
+
+    let leg = Leg(Meters(1.0), vec![Meters(2.0)]);
+    let bytes = MemoryPackSerializer::serialize(&leg).unwrap();
+    assert_eq!(MemoryPackSerializer::deserialize::<Leg>(&bytes).unwrap(), leg);
+}
+
+#[test]
+fn hand_written_fields_are_opaque() {
+    assert_eq!(
+        members(Route::schema()),
+        [
+            Schema::Primitive(Primitive::U32),
+            Schema::Custom("Meters"),
+            Schema::Custom("Option<Meters>"),
+            Schema::Custom("Distance"),
+        ]
+    );
+    assert_eq!(
+        members(Leg::schema()),
+        [Schema::Custom("Meters"), Schema::Custom("Vec<Meters>")]
+    );
+}
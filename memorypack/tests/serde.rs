@@ -0,0 +1,78 @@
+use std::collections::HashMap;
+use std::io::Cursor;
+
+use memorypack::serde::{Deserializer, from_slice, to_vec};
+use memorypack::MemoryPackReader;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+struct Profile {
+    id: u32,
+    name: String,
+    nickname: Option<String>,
+    age: Option<u8>,
+    manager: Option<Box<Profile>>,
+    history: Vec<Option<i64>>,
+    settings: HashMap<String, Option<bool>>,
+    status: Status,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+enum Status {
+    Active,
+    Away(Option<String>),
+    Left { reason: Option<String> },
+}
+
+fn profile() -> Profile {
+    Profile {
+        id: 3,
+        name: "Grace".into(),
+        nickname: None,
+        age: Some(255),
+        manager: Some(Box::new(Profile {
+            id: 1,
+            name: "Ada".into(),
+            nickname: Some("countess".into()),
+            age: None,
+            manager: None,
+            history: vec![],
+            settings: HashMap::new(),
+            status: Status::Left { reason: None },
+        })),
+        history: vec![Some(-1), None, Some(i64::MAX)],
+        settings: HashMap::from([("dark".into(), Some(true)), ("sound".into(), None)]),
+        status: Status::Away(Some("lunch".into())),
+    }
+}
+
+#[test]
+fn round_trip_with_options() {
+    let value = profile();
+    let bytes = to_vec(&value).unwrap();
+    assert_eq!(from_slice::<Profile>(&bytes).unwrap(), value);
+
+    let mut cursor = Cursor::new(&bytes);
+    let mut reader = MemoryPackReader::from_stream(&mut cursor);
+    let back = Profile::deserialize(&mut Deserializer::new(&mut reader)).unwrap();
+    assert_eq!(back, value);
+}
+
+#[test]
+fn option_layout() {
+    assert_eq!(to_vec(&None::<u8>).unwrap(), [0, 0, 0, 0]);
+    assert_eq!(to_vec(&Some(255u8)).unwrap(), [1, 0, 0, 0, 255]);
+    assert_eq!(to_vec(&Some(None::<u8>)).unwrap(), [1, 0, 0, 0, 0, 0, 0, 0]);
+    assert_eq!(from_slice::<Option<u8>>(&[1, 0, 0, 0, 255]).unwrap(), Some(255));
+    assert_eq!(from_slice::<Option<Option<u8>>>(&[1, 0, 0, 0, 0, 0, 0, 0]).unwrap(), Some(None));
+
+    let result = from_slice::<Option<u8>>(&[2, 0, 0, 0, 0]);
+    assert!(result.unwrap_err().to_string().contains("has-value flag 2"));
+}
+
+#[test]
+fn arrays_are_tuples() {
+    let bytes = to_vec(&[1u16, 2, 3]).unwrap();
+    assert_eq!(bytes, [1, 0, 2, 0, 3, 0]);
+    assert_eq!(from_slice::<[u16; 3]>(&bytes).unwrap(), [1, 2, 3]);
+}
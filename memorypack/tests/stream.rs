@@ -0,0 +1,170 @@
+use std::collections::HashMap;
+use std::io::{Cursor, Read};
+
+use memorypack::prelude::*;
+use memorypack::{MemoryPackReader, MemoryPackWriter};
+
+#[derive(MemoryPackable, Debug, Clone, PartialEq, Default)]
+struct Person {
+    id: i32,
+    name: String,
+    tags: Vec<String>,
+    scores: HashMap<String, f64>,
+    samples: Vec<u64>,
+    parent: Option<Box<Person>>,
+}
+
+#[derive(MemoryPackable, Debug, Clone, PartialEq, Default)]
+enum Shape {
+    #[default]
+    #[memorypack(tag = 0)]
+    Empty,
+    #[memorypack(tag = 1)]
+    Circle { radius: f32 },
+    #[memorypack(tag = 2)]
+    Polygon { points: Vec<(i32, i32)> },
+}
+
+#[derive(MemoryPackable, Debug, Clone, PartialEq)]
+struct Scene {
+    shapes: Vec<Option<Shape>>,
+    title: Option<String>,
+}
+
+/// `Read` that returns at most `chunk` bytes per call
+struct ShortReads<'a> {
+    data: &'a [u8],
+    chunk: usize,
+}
+
+impl Read for ShortReads<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let len = buf.len().min(self.chunk).min(self.data.len());
+        buf[..len].copy_from_slice(&self.data[..len]);
+        self.data = &self.data[len..];
+        Ok(len)
+    }
+}
+
+fn person() -> Person {
+    Person {
+        id: 7,
+        name: "Ada Lovelace".into(),
+        tags: vec!["math".into(), "engines".into()],
+        scores: HashMap::from([("analysis".into(), 9.5), ("poetry".into(), 6.0)]),
+        samples: (0..2_000).collect(),
+        parent: Some(Box::new(Person {
+            id: 1,
+            name: "Byron".into(),
+            ..Person::default()
+        })),
+    }
+}
+
+fn scene() -> Scene {
+    Scene {
+        shapes: vec![
+            Some(Shape::Circle { radius: 1.5 }),
+            None,
+            Some(Shape::Empty),
+            Some(Shape::Polygon {
+                points: vec![(0, 0), (4, 0), (4, 3)],
+            }),
+        ],
+        title: Some("triangle".into()),
+    }
+}
+
+#[test]
+fn cursor_round_trip() {
+    let value = person();
+    let mut cursor = Cursor::new(Vec::new());
+    MemoryPackSerializer::serialize_to_stream(&value, &mut cursor).unwrap();
+
+    let bytes = cursor.into_inner();
+    assert_eq!(bytes, MemoryPackSerializer::serialize(&value).unwrap());
+
+    let back: Person = MemoryPackSerializer::deserialize_from_stream(Cursor::new(&bytes)).unwrap();
+    assert_eq!(back, value);
+}
+
+#[test]
+fn stream_writer_flushes_through_small_buffer() {
+    let value = person();
+    let mut sink = Vec::new();
+    {
+        let mut writer = MemoryPackWriter::from_stream_with_capacity(&mut sink, 16);
+        MemoryPackSerializer::serialize_to(&value, &mut writer).unwrap();
+        writer.flush().unwrap();
+    }
+    assert_eq!(sink, MemoryPackSerializer::serialize(&value).unwrap());
+}
+
+#[test]
+fn consecutive_values_share_a_stream() {
+    let mut cursor = Cursor::new(Vec::new());
+    MemoryPackSerializer::serialize_to_stream(&person(), &mut cursor).unwrap();
+    MemoryPackSerializer::serialize_to_stream(&scene(), &mut cursor).unwrap();
+
+    cursor.set_position(0);
+    let first: Person = MemoryPackSerializer::deserialize_from_stream(&mut cursor).unwrap();
+    let second: Scene = MemoryPackSerializer::deserialize_from_stream(&mut cursor).unwrap();
+    assert_eq!(first, person());
+    assert_eq!(second, scene());
+    assert_eq!(cursor.position() as usize, cursor.get_ref().len());
+}
+
+#[test]
+fn short_reads_round_trip() {
+    let bytes = MemoryPackSerializer::serialize(&scene()).unwrap();
+    for chunk in [1, 2, 3, 7, 64] {
+        let mut source = ShortReads { data: &bytes, chunk };
+        let back: Scene = MemoryPackSerializer::deserialize_from_stream(&mut source).unwrap();
+        assert_eq!(back, scene(), "chunk size {chunk}");
+    }
+
+    let bytes = MemoryPackSerializer::serialize(&person()).unwrap();
+    let mut source = ShortReads { data: &bytes, chunk: 5 };
+    let back: Person = MemoryPackSerializer::deserialize_from_stream(&mut source).unwrap();
+    assert_eq!(back, person());
+}
+
+#[test]
+fn peek_does_not_consume() {
+    let bytes = MemoryPackSerializer::serialize(&(Option::<Shape>::None, 42u8)).unwrap();
+    let mut source = ShortReads { data: &bytes, chunk: 1 };
+    let mut reader = MemoryPackReader::from_stream(&mut source);
+
+    assert_eq!(reader.peek_u8().unwrap(), 255);
+    assert_eq!(reader.peek_u8().unwrap(), 255);
+    assert_eq!(reader.read_u8().unwrap(), 255);
+    assert_eq!(reader.read_u8().unwrap(), 42);
+    assert!(reader.peek_u8().is_err());
+}
+
+#[test]
+fn stream_reader_cannot_rewind() {
+    let bytes = [1u8, 2, 3, 4];
+    let mut source = Cursor::new(&bytes);
+    let mut reader = MemoryPackReader::from_stream(&mut source);
+
+    reader.read_i32().unwrap();
+    assert!(reader.rewind(4).is_err());
+}
+
+#[test]
+fn truncated_stream_errors() {
+    let bytes = MemoryPackSerializer::serialize(&person()).unwrap();
+    for cut in [0, 1, 5, bytes.len() / 2, bytes.len() - 1] {
+        let result = MemoryPackSerializer::deserialize_from_stream::<Person, _>(Cursor::new(&bytes[..cut]));
+        assert!(result.is_err(), "cut at {cut}");
+
+        let mut source = ShortReads { data: &bytes[..cut], chunk: 3 };
+        let result = MemoryPackSerializer::deserialize_from_stream::<Person, _>(&mut source);
+        assert!(result.is_err(), "cut at {cut} with short reads");
+    }
+
+    let bytes = MemoryPackSerializer::serialize(&scene()).unwrap();
+    let result = MemoryPackSerializer::deserialize_from_stream::<Scene, _>(Cursor::new(&bytes[..bytes.len() - 2]));
+    assert!(result.is_err());
+}